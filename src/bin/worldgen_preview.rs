@@ -0,0 +1,55 @@
+//! Prints a map of the world generated for a seed and region, without opening the game.
+//!
+//! Usage: worldgen_preview [--seed N] [--from X,Y] [--to X,Y] [--manifests PATH]
+//!
+//! The region is given in chunks, inclusive of both corners.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use bevy::math::{IRect, IVec2};
+use fear_factory::preview::preview;
+
+const USAGE: &str = "Usage: worldgen_preview [--seed N] [--from X,Y] [--to X,Y] [--manifests PATH]";
+
+fn main() -> ExitCode {
+    let mut seed = None;
+    let mut from = IVec2::new(-2, -2);
+    let mut to = IVec2::new(1, 1);
+    let mut manifests = PathBuf::from("assets/manifests");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next();
+        let parsed = match (flag.as_str(), value.as_deref()) {
+            ("--seed", Some(value)) => value.parse().ok().map(|value| seed = Some(value)),
+            ("--from", Some(value)) => parse_chunk(value).map(|chunk| from = chunk),
+            ("--to", Some(value)) => parse_chunk(value).map(|chunk| to = chunk),
+            ("--manifests", Some(value)) => {
+                manifests = PathBuf::from(value);
+                Some(())
+            }
+            _ => None,
+        };
+
+        if parsed.is_none() {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    match preview(&manifests, seed, IRect::from_corners(from, to)) {
+        Ok(map) => {
+            print!("{map}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_chunk(value: &str) -> Option<IVec2> {
+    let (x, y) = value.split_once(',')?;
+    Some(IVec2::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
@@ -0,0 +1,222 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::{
+    gameplay::{
+        blueprint::{
+            Blueprint, BlueprintClipboard, BlueprintConstruct, BlueprintEntry, BlueprintTool,
+        },
+        hud::hotbar::HotbarSelection,
+        recipe::{assets::Recipe, select::SelectedRecipe},
+        structure::{
+            Structure,
+            assets::StructureDef,
+            orientation::Orientation,
+            path::{PathDef, PathSegment},
+        },
+        world::{
+            construction::Constructions,
+            construction_site::ConstructionSite,
+            tilemap::coord::{Coord, translation_to_coord},
+        },
+    },
+    input::{
+        cursor::CursorPosition,
+        input_map::{Action, action_just_pressed},
+    },
+    widgets::tooltip::{HideTooltip, ShowTooltip, TooltipSource},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CaptureDrag>();
+
+    app.add_observer(start_capture);
+    app.add_observer(finish_capture);
+
+    app.add_systems(
+        Update,
+        (
+            toggle_capture.run_if(action_just_pressed(Action::CopyBlueprint)),
+            show_capture_area.run_if(resource_equals(BlueprintTool::Capturing)),
+        )
+            .chain(),
+    );
+}
+
+/// Corner the capture area is being dragged out from
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct CaptureDrag(pub Option<IVec2>);
+
+/// Lowest and highest corners of the area dragged between two tiles
+pub fn capture_area(start: IVec2, end: IVec2) -> (IVec2, IVec2) {
+    (start.min(end), start.max(end))
+}
+
+fn toggle_capture(
+    mut tool: ResMut<BlueprintTool>,
+    mut capture_drag: ResMut<CaptureDrag>,
+    hotbar_selection: HotbarSelection,
+    mut commands: Commands,
+) {
+    capture_drag.0 = None;
+
+    if *tool == BlueprintTool::Capturing {
+        *tool = BlueprintTool::Idle;
+        commands.trigger(HideTooltip(TooltipSource::Blueprint));
+    } else if hotbar_selection.action().is_none() {
+        *tool = BlueprintTool::Capturing;
+    }
+}
+
+fn show_capture_area(
+    capture_drag: Res<CaptureDrag>,
+    cursor_position: Res<CursorPosition>,
+    mut commands: Commands,
+) {
+    let Some(start) = capture_drag.0 else {
+        commands.trigger(ShowTooltip {
+            source: TooltipSource::Blueprint,
+            text: "Drag over an area to copy it".into(),
+        });
+        return;
+    };
+
+    let (min, max) = capture_area(start, translation_to_coord(&cursor_position.0).0);
+    let size = max - min + IVec2::ONE;
+
+    commands.trigger(ShowTooltip {
+        source: TooltipSource::Blueprint,
+        text: format!("{} x {} tiles", size.x, size.y),
+    });
+}
+
+fn start_capture(
+    drag_start: On<Pointer<DragStart>>,
+    ui_nodes: Query<(), With<Node>>,
+    tool: Res<BlueprintTool>,
+    cursor_position: Res<CursorPosition>,
+    mut capture_drag: ResMut<CaptureDrag>,
+) {
+    if drag_start.button != PointerButton::Primary || ui_nodes.contains(drag_start.entity) {
+        return;
+    }
+
+    if *tool != BlueprintTool::Capturing || capture_drag.0.is_some() {
+        return;
+    }
+
+    capture_drag.0 = Some(translation_to_coord(&cursor_position.0).0);
+}
+
+/// Copies every construct with its origin inside the dragged area into the clipboard. Junctions
+/// are left out, as they replace the path segment they were built on and can't be laid out alone.
+fn finish_capture(
+    drag_end: On<Pointer<DragEnd>>,
+    mut tool: ResMut<BlueprintTool>,
+    mut capture_drag: ResMut<CaptureDrag>,
+    mut clipboard: ResMut<BlueprintClipboard>,
+    cursor_position: Res<CursorPosition>,
+    constructions: Res<Constructions>,
+    coords: Query<&Coord>,
+    structures: Query<(&Structure, &Orientation, Option<&SelectedRecipe>)>,
+    sites: Query<&ConstructionSite>,
+    path_segments: Query<&PathSegment>,
+    structure_defs: Res<Assets<StructureDef>>,
+    path_defs: Res<Assets<PathDef>>,
+    recipes: Res<Assets<Recipe>>,
+    mut commands: Commands,
+) {
+    if drag_end.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(start) = capture_drag.0.take() else {
+        return;
+    };
+
+    let (min, max) = capture_area(start, translation_to_coord(&cursor_position.0).0);
+
+    let constructs: HashSet<Entity> = constructions.values().copied().collect();
+    let mut entries = Vec::new();
+
+    for entity in constructs {
+        let Ok(coord) = coords.get(entity) else {
+            continue;
+        };
+
+        if coord.0.cmplt(min).any() || coord.0.cmpgt(max).any() {
+            continue;
+        }
+
+        let (construct, orientation, recipe) =
+            if let Ok((structure, orientation, selected_recipe)) = structures.get(entity) {
+                let Some(structure_def) = structure_defs
+                    .get(&structure.0)
+                    .filter(|structure_def| structure_def.junction.is_none())
+                else {
+                    continue;
+                };
+
+                let recipe = selected_recipe
+                    .and_then(|selected| recipes.get(&selected.0))
+                    .map(|recipe| recipe.id.clone());
+
+                (
+                    BlueprintConstruct::Structure(structure_def.id.clone()),
+                    *orientation,
+                    recipe,
+                )
+            } else if let Ok(site) = sites.get(entity) {
+                let Some(structure_def) = structure_defs
+                    .get(&site.structure)
+                    .filter(|structure_def| structure_def.junction.is_none())
+                else {
+                    continue;
+                };
+
+                let recipe = site
+                    .recipe
+                    .and_then(|recipe| recipes.get(recipe))
+                    .map(|recipe| recipe.id.clone());
+
+                (
+                    BlueprintConstruct::Structure(structure_def.id.clone()),
+                    site.orientation,
+                    recipe,
+                )
+            } else if let Ok(path_segment) = path_segments.get(entity) {
+                let Some(path_def) = path_defs.get(&path_segment.0) else {
+                    continue;
+                };
+
+                (
+                    BlueprintConstruct::Path(path_def.id.clone()),
+                    Orientation::default(),
+                    None,
+                )
+            } else {
+                continue;
+            };
+
+        entries.push(BlueprintEntry {
+            offset: (coord.0 - min).to_array(),
+            construct,
+            orientation: orientation.0,
+            recipe,
+        });
+    }
+
+    entries.sort_by_key(|entry| (entry.offset[1], entry.offset[0]));
+
+    if !entries.is_empty() {
+        clipboard.0 = Some(Blueprint {
+            name: "Blueprint".into(),
+            entries,
+        });
+    }
+
+    *tool = BlueprintTool::Idle;
+    commands.trigger(HideTooltip(TooltipSource::Blueprint));
+}
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{hud::hotbar::HotbarSelectionChanged, structure::orientation::Direction},
+    input::input_map::{Action, action_just_pressed},
+    widgets::tooltip::{HideTooltip, TooltipSource},
+};
+
+pub mod capture;
+pub mod paste;
+pub mod storage;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<BlueprintTool>();
+    app.init_resource::<BlueprintClipboard>();
+
+    app.add_plugins((capture::plugin, paste::plugin, storage::plugin));
+
+    app.add_systems(
+        Update,
+        (
+            leave_tool.run_if(action_just_pressed(Action::Dismiss)),
+            leave_tool.run_if(on_message::<HotbarSelectionChanged>),
+        ),
+    );
+}
+
+/// Layout of constructs that can be pasted elsewhere, stored by manifest id so it can be shared
+/// between saves
+#[derive(Reflect, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Blueprint {
+    pub name: String,
+    #[serde(default, rename = "entry")]
+    pub entries: Vec<BlueprintEntry>,
+}
+
+/// Construct placed at an offset from the corner of the blueprint
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlueprintEntry {
+    pub offset: [i32; 2],
+    #[serde(flatten)]
+    pub construct: BlueprintConstruct,
+    #[serde(default)]
+    pub orientation: Direction,
+    /// Recipe id selected on the structure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipe: Option<String>,
+}
+
+#[derive(Reflect, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlueprintConstruct {
+    /// Structure manifest id
+    Structure(String),
+    /// Path manifest id
+    Path(String),
+}
+
+impl BlueprintEntry {
+    pub fn offset(&self) -> IVec2 {
+        IVec2::from_array(self.offset)
+    }
+}
+
+impl Blueprint {
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    pub fn from_toml(source: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(source)
+    }
+}
+
+/// What clicking and dragging on the world does while no hotbar action is selected
+#[derive(Resource, Reflect, Debug, Default, PartialEq, Eq)]
+#[reflect(Resource)]
+pub enum BlueprintTool {
+    #[default]
+    Idle,
+    /// Dragging out an area copies it into the clipboard
+    Capturing,
+    /// Clicking pastes the clipboard with its corner at the cursor
+    Pasting,
+}
+
+/// Blueprint most recently copied or loaded
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct BlueprintClipboard(pub Option<Blueprint>);
+
+fn leave_tool(mut tool: ResMut<BlueprintTool>, mut commands: Commands) {
+    if *tool != BlueprintTool::Idle {
+        *tool = BlueprintTool::Idle;
+        commands.trigger(HideTooltip(TooltipSource::Blueprint));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blueprint_round_trips_through_toml() {
+        let blueprint = Blueprint {
+            name: "Smeltery".into(),
+            entries: vec![
+                BlueprintEntry {
+                    offset: [0, 0],
+                    construct: BlueprintConstruct::Structure("crafter".into()),
+                    orientation: Direction::East,
+                    recipe: Some("iron_ingot".into()),
+                },
+                BlueprintEntry {
+                    offset: [1, 2],
+                    construct: BlueprintConstruct::Path("dirt".into()),
+                    orientation: Direction::North,
+                    recipe: None,
+                },
+            ],
+        };
+
+        let source = blueprint.to_toml().unwrap();
+
+        assert!(source.contains("structure = \"crafter\""));
+        assert_eq!(Blueprint::from_toml(&source).unwrap(), blueprint);
+    }
+}
@@ -0,0 +1,543 @@
+use std::collections::HashMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*, sprite::Anchor};
+use bevy_aseprite_ultra::prelude::*;
+
+use crate::{
+    assets::indexing::IndexMap,
+    gameplay::{
+        blueprint::{
+            Blueprint, BlueprintClipboard, BlueprintConstruct, BlueprintEntry, BlueprintTool,
+        },
+        hud::hotbar::HotbarSelection,
+        inventory::prelude::*,
+        player::Player,
+        recipe::{assets::Recipe, select::SelectRecipe},
+        sprite_sort::{YSortSprite, ZIndexSprite},
+        structure::{
+            assets::StructureDef,
+            orientation::Orientation,
+            path::{PathDef, PathSegment, path_segment, segment_slice_name},
+            placement_rule::TileContent,
+        },
+        world::{
+            construction::{
+                ConstructKind, ConstructionPreview, Constructions, PlacementSurroundings,
+                StructureConstructed, spawn_structure,
+            },
+            construction_site::construction_site,
+            history::{History, HistoryStep, PlacedConstruct},
+            tilemap::coord::{Coord, translation_to_coord},
+        },
+    },
+    input::{
+        cursor::CursorPosition,
+        input_map::{Action, action_just_pressed},
+    },
+    widgets::tooltip::{HideTooltip, ShowTooltip, TooltipSource},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(paste_on_click);
+
+    app.add_systems(
+        Update,
+        (
+            toggle_paste.run_if(action_just_pressed(Action::PasteBlueprint)),
+            refresh_paste_preview.run_if(
+                resource_changed::<BlueprintTool>.or(resource_changed::<BlueprintClipboard>),
+            ),
+            move_paste_preview.run_if(resource_equals(BlueprintTool::Pasting)),
+        )
+            .chain(),
+    );
+}
+
+/// Construction preview standing in for one blueprint entry while pasting
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(ConstructionPreview)]
+pub struct BlueprintPreview {
+    pub offset: IVec2,
+}
+
+/// Blueprint entry resolved against the loaded manifests
+pub enum ResolvedConstruct<'a> {
+    Structure {
+        def: &'a StructureDef,
+        handle: Handle<StructureDef>,
+        orientation: Orientation,
+        recipe: Option<AssetId<Recipe>>,
+    },
+    Path {
+        def: &'a PathDef,
+        handle: Handle<PathDef>,
+    },
+}
+
+impl ResolvedConstruct<'_> {
+    /// Tiles covered once placed with the blueprint's corner at `origin`
+    pub fn tiles(&self, origin: IVec2, offset: IVec2) -> Vec<IVec2> {
+        match self {
+            ResolvedConstruct::Structure {
+                def, orientation, ..
+            } => orientation
+                .footprint(&def.footprint)
+                .tiles(origin + offset)
+                .collect(),
+            ResolvedConstruct::Path { .. } => vec![origin + offset],
+        }
+    }
+
+    /// Whether the construct may be placed over rivers
+    pub fn spans_water(&self) -> bool {
+        matches!(self, ResolvedConstruct::Path { def, .. } if def.bridge)
+    }
+
+    /// Record of the construct placed at `coord`, for undoing the paste
+    pub fn placed(&self, coord: IVec2) -> PlacedConstruct {
+        match self {
+            ResolvedConstruct::Structure {
+                def,
+                handle,
+                orientation,
+                recipe,
+            } => PlacedConstruct {
+                coord,
+                construct: if def.build_time.is_some() {
+                    ConstructKind::Site {
+                        structure: handle.clone(),
+                        delivered: HashMap::new(),
+                    }
+                } else {
+                    ConstructKind::Structure(handle.clone())
+                },
+                orientation: *orientation,
+                recipe: *recipe,
+            },
+            ResolvedConstruct::Path { handle, .. } => {
+                PlacedConstruct::new(coord, ConstructKind::Path(handle.clone()))
+            }
+        }
+    }
+
+    /// Cost paid up front, leaving construction sites to their porters
+    pub fn cost(&self) -> Option<&HashMap<Handle<ItemDef>, u32>> {
+        match self {
+            ResolvedConstruct::Structure { def, .. } => {
+                def.build_time.is_none().then_some(&def.cost)
+            }
+            ResolvedConstruct::Path { def, .. } => Some(&def.cost),
+        }
+    }
+}
+
+/// Looks up the manifests blueprint entries refer to by id
+#[derive(SystemParam)]
+pub struct BlueprintManifests<'w> {
+    structure_index: Res<'w, IndexMap<StructureDef>>,
+    path_index: Res<'w, IndexMap<PathDef>>,
+    recipe_index: Res<'w, IndexMap<Recipe>>,
+    structure_defs: Res<'w, Assets<StructureDef>>,
+    path_defs: Res<'w, Assets<PathDef>>,
+    asset_server: Res<'w, AssetServer>,
+}
+
+impl BlueprintManifests<'_> {
+    pub fn resolve(&self, entry: &BlueprintEntry) -> Option<ResolvedConstruct<'_>> {
+        match &entry.construct {
+            BlueprintConstruct::Structure(id) => {
+                let asset_id = *self.structure_index.get(id)?;
+
+                Some(ResolvedConstruct::Structure {
+                    def: self.structure_defs.get(asset_id)?,
+                    handle: self.asset_server.get_id_handle(asset_id)?,
+                    orientation: Orientation(entry.orientation),
+                    recipe: entry
+                        .recipe
+                        .as_ref()
+                        .and_then(|recipe| self.recipe_index.get(recipe))
+                        .copied(),
+                })
+            }
+            BlueprintConstruct::Path(id) => {
+                let asset_id = *self.path_index.get(id)?;
+
+                Some(ResolvedConstruct::Path {
+                    def: self.path_defs.get(asset_id)?,
+                    handle: self.asset_server.get_id_handle(asset_id)?,
+                })
+            }
+        }
+    }
+
+    /// Total cost of pasting the blueprint, summed over every construct paid for up front
+    pub fn cost(&self, blueprint: &Blueprint) -> HashMap<Handle<ItemDef>, u32> {
+        let mut total = HashMap::new();
+
+        for entry in blueprint.entries.iter() {
+            let Some(cost) = self
+                .resolve(entry)
+                .and_then(|resolved| resolved.cost().cloned())
+            else {
+                continue;
+            };
+
+            for (item, quantity) in cost {
+                *total.entry(item).or_default() += quantity;
+            }
+        }
+
+        total
+    }
+
+    /// Whether every entry is known and fits on empty, explored tiles without overlapping the
+    /// others, only bridges cross rivers, and the placement rules of each structure hold. Paths of
+    /// the blueprint count towards the rules as if already built. Junctions can't be pasted, having
+    /// no path segment to go on.
+    pub fn placement_allowed(
+        &self,
+        blueprint: &Blueprint,
+        origin: IVec2,
+        constructions: &Constructions,
+        path_segments: &Query<&PathSegment>,
+        surroundings: &PlacementSurroundings,
+    ) -> bool {
+        let paths: Vec<IVec2> = blueprint
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry.construct, BlueprintConstruct::Path(_)))
+            .map(|entry| origin + entry.offset())
+            .collect();
+
+        let content = |tile: IVec2| {
+            if paths.contains(&tile) {
+                TileContent::Path
+            } else {
+                surroundings.content(tile, constructions, path_segments)
+            }
+        };
+
+        let mut covered = Vec::new();
+
+        for entry in blueprint.entries.iter() {
+            let Some(resolved) = self.resolve(entry) else {
+                return false;
+            };
+
+            for tile in resolved.tiles(origin, entry.offset()) {
+                if constructions.contains_key(&tile)
+                    || covered.contains(&tile)
+                    || !surroundings.is_revealed(tile)
+                    || (!resolved.spans_water() && surroundings.is_river(tile))
+                {
+                    return false;
+                }
+
+                covered.push(tile);
+            }
+
+            let ResolvedConstruct::Structure {
+                def, orientation, ..
+            } = resolved
+            else {
+                continue;
+            };
+
+            let coord = origin + entry.offset();
+            let footprint = orientation.footprint(&def.footprint);
+
+            if def.junction.is_some()
+                || !def.placement_rules.iter().all(|rule| {
+                    rule.satisfied(coord, &footprint, content, |tile| surroundings.biome(tile))
+                })
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+fn toggle_paste(
+    mut tool: ResMut<BlueprintTool>,
+    clipboard: Res<BlueprintClipboard>,
+    hotbar_selection: HotbarSelection,
+    mut commands: Commands,
+) {
+    if *tool == BlueprintTool::Pasting {
+        *tool = BlueprintTool::Idle;
+        commands.trigger(HideTooltip(TooltipSource::Blueprint));
+    } else if clipboard.0.is_some() && hotbar_selection.action().is_none() {
+        *tool = BlueprintTool::Pasting;
+    }
+}
+
+fn refresh_paste_preview(
+    tool: Res<BlueprintTool>,
+    clipboard: Res<BlueprintClipboard>,
+    previews: Query<Entity, With<BlueprintPreview>>,
+    manifests: BlueprintManifests,
+    mut commands: Commands,
+) {
+    for preview in previews {
+        commands.entity(preview).despawn();
+    }
+
+    let (BlueprintTool::Pasting, Some(blueprint)) = (&*tool, &clipboard.0) else {
+        return;
+    };
+
+    let paths: Vec<IVec2> = blueprint
+        .entries
+        .iter()
+        .filter(|entry| matches!(entry.construct, BlueprintConstruct::Path(_)))
+        .map(|entry| entry.offset())
+        .collect();
+
+    for entry in blueprint.entries.iter() {
+        let Some(resolved) = manifests.resolve(entry) else {
+            continue;
+        };
+
+        let offset = entry.offset();
+
+        let preview = commands
+            .spawn((
+                Name::new("Blueprint Preview"),
+                BlueprintPreview { offset },
+                Coord(offset),
+                YSortSprite,
+                ZIndexSprite(10),
+            ))
+            .id();
+
+        match resolved {
+            ResolvedConstruct::Structure {
+                def, orientation, ..
+            } => {
+                commands.entity(preview).insert((
+                    orientation.footprint(&def.footprint),
+                    Sprite {
+                        flip_x: orientation.flips_sprite(),
+                        ..default()
+                    },
+                    Anchor(Vec2::new(0.0, -0.33)),
+                    AseAnimation {
+                        aseprite: manifests.asset_server.load(&def.sprite),
+                        animation: Animation::tag("work"),
+                    },
+                ));
+            }
+            ResolvedConstruct::Path { def, .. } => {
+                commands.entity(preview).insert((
+                    Sprite::default(),
+                    AseSlice {
+                        aseprite: manifests.asset_server.load(&def.sprite),
+                        name: segment_slice_name(offset, |c| paths.contains(&c)),
+                    },
+                ));
+            }
+        }
+    }
+}
+
+/// What the paste preview last showed, so it is only redrawn once something it shows changes
+#[derive(Default)]
+struct PastePreviewState {
+    cost: HashMap<Handle<ItemDef>, u32>,
+    /// Origin of the preview and whether it could be placed and afforded there
+    shown: Option<(IVec2, bool, bool)>,
+}
+
+fn move_paste_preview(
+    mut state: Local<PastePreviewState>,
+    tool: Res<BlueprintTool>,
+    clipboard: Res<BlueprintClipboard>,
+    cursor_position: Res<CursorPosition>,
+    previews: Query<(&BlueprintPreview, &mut Coord, &mut Sprite)>,
+    manifests: BlueprintManifests,
+    constructions: Res<Constructions>,
+    path_segments: Query<&PathSegment>,
+    surroundings: PlacementSurroundings,
+    item_defs: Res<Assets<ItemDef>>,
+    player: Single<Entity, With<Player>>,
+    inventory: Query<&Inventory>,
+    stacks: Query<&mut ItemStack>,
+    mut commands: Commands,
+) {
+    let Some(blueprint) = &clipboard.0 else {
+        return;
+    };
+
+    // The previews and the tooltip are fresh whenever the tool or the clipboard changed
+    if tool.is_changed() || clipboard.is_changed() {
+        state.cost = manifests.cost(blueprint);
+        state.shown = None;
+
+        let mut lines = vec![format!(
+            "{} ({} constructs)",
+            blueprint.name,
+            blueprint.entries.len()
+        )];
+
+        for (item, quantity) in state.cost.iter() {
+            if let Some(item_def) = item_defs.get(item) {
+                lines.push(format!("{}: {quantity}", item_def.name));
+            }
+        }
+
+        commands.trigger(ShowTooltip {
+            source: TooltipSource::Blueprint,
+            text: lines.join("\n"),
+        });
+    }
+
+    let origin = translation_to_coord(&cursor_position.0).0;
+    let allowed = manifests.placement_allowed(
+        blueprint,
+        origin,
+        &constructions,
+        &path_segments,
+        &surroundings,
+    );
+    let affordable = can_afford(*player, &state.cost, &inventory, &stacks);
+
+    if state.shown == Some((origin, allowed, affordable)) {
+        return;
+    }
+
+    state.shown = Some((origin, allowed, affordable));
+
+    let color = if !allowed || !affordable {
+        Color::hsl(0.0, 1.0, 0.5)
+    } else {
+        Color::default().with_alpha(0.5)
+    };
+
+    for (preview, mut coord, mut sprite) in previews {
+        coord.0 = origin + preview.offset;
+        sprite.color = color;
+    }
+}
+
+/// Places every construct of the clipboard with its corner at the cursor, paying for all of them
+/// at once
+fn paste_on_click(
+    click: On<Pointer<Click>>,
+    ui_nodes: Query<(), With<Node>>,
+    tool: Res<BlueprintTool>,
+    clipboard: Res<BlueprintClipboard>,
+    cursor_position: Res<CursorPosition>,
+    manifests: BlueprintManifests,
+    mut constructions: ResMut<Constructions>,
+    path_segments: Query<&PathSegment>,
+    surroundings: PlacementSurroundings,
+    mut structures_constructed: MessageWriter<StructureConstructed>,
+    player: Single<Entity, With<Player>>,
+    inventory: Query<&Inventory>,
+    mut stacks: Query<&mut ItemStack>,
+    mut history: ResMut<History>,
+    mut commands: Commands,
+) {
+    if click.button != PointerButton::Primary || ui_nodes.contains(click.entity) {
+        return;
+    }
+
+    let (BlueprintTool::Pasting, Some(blueprint)) = (&*tool, &clipboard.0) else {
+        return;
+    };
+
+    let origin = translation_to_coord(&cursor_position.0).0;
+
+    if !manifests.placement_allowed(
+        blueprint,
+        origin,
+        &constructions,
+        &path_segments,
+        &surroundings,
+    ) {
+        return;
+    }
+
+    let cost = manifests.cost(blueprint);
+
+    if !can_afford(*player, &cost, &inventory, &stacks) {
+        return;
+    }
+
+    spend(*player, &cost, &inventory, &mut stacks);
+
+    let mut placed = Vec::new();
+
+    for entry in blueprint.entries.iter() {
+        let Some(resolved) = manifests.resolve(entry) else {
+            continue;
+        };
+
+        let coord = origin + entry.offset();
+        let tiles = resolved.tiles(origin, entry.offset());
+
+        placed.push(resolved.placed(coord));
+
+        let entity = match resolved {
+            ResolvedConstruct::Structure {
+                def,
+                handle,
+                orientation,
+                recipe,
+            } if def.build_time.is_some() => commands
+                .spawn(construction_site(
+                    def,
+                    handle,
+                    coord,
+                    orientation,
+                    recipe,
+                    &manifests.asset_server,
+                ))
+                .id(),
+            ResolvedConstruct::Structure {
+                def,
+                handle,
+                orientation,
+                recipe,
+            } => {
+                let entity = spawn_structure(
+                    &mut commands,
+                    def,
+                    handle,
+                    coord,
+                    orientation,
+                    &manifests.asset_server,
+                );
+
+                if let Some(recipe) = recipe {
+                    commands.trigger(SelectRecipe { entity, recipe });
+                }
+
+                structures_constructed.write(StructureConstructed(entity));
+
+                entity
+            }
+            ResolvedConstruct::Path { def, handle } => {
+                let entity = commands
+                    .spawn(path_segment(def, handle, coord, &manifests.asset_server))
+                    .id();
+
+                structures_constructed.write(StructureConstructed(entity));
+
+                entity
+            }
+        };
+
+        for tile in tiles {
+            constructions.insert(tile, entity);
+        }
+    }
+
+    history.record(HistoryStep::Constructs {
+        placed,
+        removed: Vec::new(),
+    });
+}
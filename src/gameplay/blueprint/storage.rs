@@ -0,0 +1,109 @@
+use std::{fs, path::PathBuf};
+
+use bevy::prelude::*;
+use thiserror::Error;
+
+use crate::gameplay::blueprint::{Blueprint, BlueprintClipboard};
+
+/// Folder blueprints are saved to, next to the game
+pub const BLUEPRINT_DIR: &str = "blueprints";
+pub const BLUEPRINT_EXTENSION: &str = "blueprint.toml";
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SavedBlueprints>();
+
+    app.add_systems(Startup, load_saved_blueprints);
+
+    app.add_observer(on_save_blueprint);
+}
+
+#[derive(Debug, Error)]
+pub enum BlueprintError {
+    #[error("Could not access blueprint file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not write blueprint: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Could not parse blueprint: {0}")]
+    Deserialize(#[from] toml::de::Error),
+}
+
+/// Blueprints found in the blueprint folder
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct SavedBlueprints(pub Vec<Blueprint>);
+
+/// Saves the clipboard to the blueprint folder under the given name
+#[derive(Event, Reflect, Debug)]
+pub struct SaveBlueprint(pub String);
+
+/// File a blueprint with the given name is saved to, keeping names readable but safe to share
+pub fn blueprint_path(name: &str) -> PathBuf {
+    let stem: String = name
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    PathBuf::from(BLUEPRINT_DIR).join(format!("{stem}.{BLUEPRINT_EXTENSION}"))
+}
+
+pub fn save_blueprint(blueprint: &Blueprint) -> Result<PathBuf, BlueprintError> {
+    let path = blueprint_path(&blueprint.name);
+
+    fs::create_dir_all(BLUEPRINT_DIR)?;
+    fs::write(&path, blueprint.to_toml()?)?;
+
+    Ok(path)
+}
+
+pub fn load_blueprint(path: &PathBuf) -> Result<Blueprint, BlueprintError> {
+    Ok(Blueprint::from_toml(&fs::read_to_string(path)?)?)
+}
+
+fn load_saved_blueprints(mut saved_blueprints: ResMut<SavedBlueprints>) {
+    // Nothing has been saved yet
+    let Ok(dir) = fs::read_dir(BLUEPRINT_DIR) else {
+        return;
+    };
+
+    let mut paths: Vec<PathBuf> = dir
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.to_string_lossy().ends_with(BLUEPRINT_EXTENSION))
+        .collect();
+
+    paths.sort();
+
+    for path in paths {
+        match load_blueprint(&path) {
+            Ok(blueprint) => saved_blueprints.0.push(blueprint),
+            Err(error) => warn!("Skipping blueprint {}: {error}", path.display()),
+        }
+    }
+}
+
+fn on_save_blueprint(
+    save_blueprint_event: On<SaveBlueprint>,
+    clipboard: Res<BlueprintClipboard>,
+    mut saved_blueprints: ResMut<SavedBlueprints>,
+) {
+    let Some(blueprint) = &clipboard.0 else {
+        return;
+    };
+
+    let blueprint = Blueprint {
+        name: save_blueprint_event.0.clone(),
+        ..blueprint.clone()
+    };
+
+    if let Err(error) = save_blueprint(&blueprint) {
+        warn!("Could not save blueprint {}: {error}", blueprint.name);
+        return;
+    }
+
+    // Saving under an existing name overwrites its file
+    saved_blueprints
+        .0
+        .retain(|saved| blueprint_path(&saved.name) != blueprint_path(&blueprint.name));
+    saved_blueprints.0.push(blueprint);
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use bevy::{asset::LoadedFolder, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    assets::{
+        indexing::{AssetIndexPlugin, Indexable},
+        loaders::toml::{FromToml, TomlAssetPlugin},
+        tracking::LoadResource,
+    },
+    gameplay::inventory::prelude::*,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins((
+        TomlAssetPlugin::<CartDef>::extensions(&["cart.toml"]),
+        AssetIndexPlugin::<CartDef>::default(),
+    ));
+
+    app.load_resource::<CartAssets>();
+}
+
+#[derive(Asset, Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct CartAssets {
+    pub manifest_folder: Handle<LoadedFolder>,
+}
+
+impl FromWorld for CartAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        Self {
+            manifest_folder: asset_server.load_folder("manifests/carts"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CartRaw {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    pub capacity: u32,
+    pub speed: f32,
+    #[serde(default)]
+    pub cost: HashMap<String, u32>,
+}
+
+/// Vehicle running scheduled routes between stations
+#[derive(Asset, Reflect, Debug)]
+pub struct CartDef {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    /// Total number of items the cart carries
+    pub capacity: u32,
+    pub speed: f32,
+    pub cost: HashMap<Handle<ItemDef>, u32>,
+}
+
+impl FromToml for CartDef {
+    type Raw = CartRaw;
+
+    fn from_toml(raw: Self::Raw, load_context: &mut bevy::asset::LoadContext) -> Self {
+        Self {
+            id: raw.id,
+            name: raw.name,
+            sprite: raw.sprite,
+            capacity: raw.capacity,
+            speed: raw.speed,
+            cost: raw
+                .cost
+                .iter()
+                .map(|(key, val)| {
+                    (
+                        load_context.load(format!("manifests/items/{key}.item.toml")),
+                        *val,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Indexable for CartDef {
+    fn index(&self) -> &String {
+        &self.id
+    }
+}
@@ -0,0 +1,350 @@
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_aseprite_ultra::prelude::{Animation, AseAnimation};
+
+use crate::gameplay::{
+    FactorySystems,
+    cart::{assets::CartDef, routing::shortest_route},
+    inventory::prelude::*,
+    people::porting::Walkable,
+    player::Player,
+    sprite_sort::{YSortSprite, ZIndexSprite},
+    structure::{
+        footprint::Footprint,
+        interactable::{Interact, Interactable},
+        path::{PathDef, PathSegment, surface_speed_multiplier},
+    },
+    tome::inspect::Inspect,
+    world::{
+        construction::Constructions,
+        tilemap::coord::{Coord, coord_to_translation, translation_to_coord},
+    },
+};
+
+pub mod assets;
+pub mod routing;
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins(assets::plugin);
+
+    app.add_observer(on_buy_cart);
+
+    app.add_systems(
+        FixedUpdate,
+        (plan_routes, drive_carts, work_docked_carts)
+            .chain()
+            .in_set(FactorySystems::Work),
+    );
+}
+
+/// Which items a cart moves at a stop
+#[derive(Reflect, Clone, Debug, Default, PartialEq)]
+pub enum CargoRule {
+    #[default]
+    Nothing,
+    Everything,
+    Matching(ItemFilter),
+}
+
+impl CargoRule {
+    pub fn admits(&self, item: &Handle<ItemDef>, item_defs: &Assets<ItemDef>) -> bool {
+        match self {
+            CargoRule::Nothing => false,
+            CargoRule::Everything => true,
+            CargoRule::Matching(filter) => filter.matches(item, item_defs),
+        }
+    }
+}
+
+/// Station a cart visits, and what it unloads and loads there
+#[derive(Reflect, Clone, Debug)]
+pub struct CartStop {
+    pub station: Entity,
+    pub unload: CargoRule,
+    pub load: CargoRule,
+}
+
+/// Player-defined stops a cart visits in order, starting over after the last
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct CartSchedule {
+    pub stops: Vec<CartStop>,
+}
+
+#[derive(Reflect, Debug, Default, PartialEq, Eq)]
+pub enum CartState {
+    /// Waiting for a schedule or a route to its next stop
+    #[default]
+    Idle,
+    Travelling,
+    /// Unloading and loading at a stop
+    Docked,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Inventory, CartSchedule, Interactable)]
+pub struct Cart {
+    pub def: Handle<CartDef>,
+    /// Index of the stop in the schedule the cart is heading to or docked at
+    pub stop: usize,
+    pub route: Vec<IVec2>,
+    pub state: CartState,
+}
+
+/// Buys a cart at the given station, spending its cost from the player inventory
+#[derive(EntityEvent, Reflect, Debug)]
+pub struct BuyCart {
+    pub entity: Entity,
+    pub cart: Handle<CartDef>,
+}
+
+fn on_buy_cart(
+    buy_cart: On<BuyCart>,
+    cart_defs: Res<Assets<CartDef>>,
+    stations: Query<(&Coord, &Footprint)>,
+    constructions: Res<Constructions>,
+    walkables: Query<(), With<Walkable>>,
+    player: Single<Entity, With<Player>>,
+    inventory: Query<&Inventory>,
+    mut stacks: Query<&mut ItemStack>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let Some(cart_def) = cart_defs.get(&buy_cart.cart) else {
+        return;
+    };
+
+    let Ok((station, footprint)) = stations.get(buy_cart.entity) else {
+        return;
+    };
+
+    let Some(depot) = footprint.neighbours(station.0).into_iter().find(|c| {
+        constructions
+            .get(c)
+            .is_some_and(|construction| walkables.contains(*construction))
+    }) else {
+        return;
+    };
+
+    if !can_afford(*player, &cart_def.cost, &inventory, &stacks) {
+        return;
+    }
+
+    spend(*player, &cart_def.cost, &inventory, &mut stacks);
+
+    commands
+        .spawn((
+            Name::new(cart_def.name.clone()),
+            Cart {
+                def: buy_cart.cart.clone(),
+                stop: 0,
+                route: Vec::new(),
+                state: CartState::Idle,
+            },
+            Transform::from_translation(coord_to_translation(&Coord(depot)).extend(0.0)),
+            Sprite::default(),
+            Anchor(Vec2::new(0.0, -0.25)),
+            AseAnimation {
+                aseprite: asset_server.load(&cart_def.sprite),
+                animation: Animation::tag("walk"),
+            },
+            YSortSprite,
+            ZIndexSprite(10),
+        ))
+        .observe(|interact: On<Interact>, mut commands: Commands| {
+            commands.trigger(Inspect {
+                entity: interact.entity,
+            });
+        });
+}
+
+fn plan_routes(
+    carts: Query<(&mut Cart, &CartSchedule, &Transform)>,
+    stations: Query<(&Coord, &Footprint)>,
+    constructions: Res<Constructions>,
+    walkables: Query<(), With<Walkable>>,
+) {
+    for (mut cart, schedule, transform) in carts {
+        if cart.state == CartState::Docked || !cart.route.is_empty() {
+            continue;
+        }
+
+        let Some(stop) = schedule.stops.get(cart.stop) else {
+            cart.stop = 0;
+            cart.state = CartState::Idle;
+            continue;
+        };
+
+        let Ok((station, footprint)) = stations.get(stop.station) else {
+            cart.stop = (cart.stop + 1) % schedule.stops.len();
+            continue;
+        };
+
+        let current = translation_to_coord(&transform.translation.xy()).0;
+        let docks = footprint.neighbours(station.0);
+
+        let route = shortest_route(
+            current,
+            |c| docks.contains(c),
+            |c| {
+                constructions
+                    .get(c)
+                    .is_some_and(|construction| walkables.contains(*construction))
+            },
+        );
+
+        match route {
+            Some(route) if route.is_empty() => cart.state = CartState::Docked,
+            Some(route) => {
+                cart.route = route;
+                cart.state = CartState::Travelling;
+            }
+            None => cart.state = CartState::Idle,
+        }
+    }
+}
+
+fn drive_carts(
+    carts: Query<(&mut Cart, &mut Transform, &mut Sprite)>,
+    cart_defs: Res<Assets<CartDef>>,
+    constructions: Res<Constructions>,
+    walkables: Query<(), With<Walkable>>,
+    path_segments: Query<&PathSegment>,
+    path_defs: Res<Assets<PathDef>>,
+    time: Res<Time>,
+) {
+    for (mut cart, mut transform, mut sprite) in carts {
+        let Some(next) = cart.route.first().copied() else {
+            continue;
+        };
+
+        let Some(cart_def) = cart_defs.get(&cart.def) else {
+            continue;
+        };
+
+        // The network changed under the cart, plan a new route from here
+        if !constructions
+            .get(&next)
+            .is_some_and(|construction| walkables.contains(*construction))
+        {
+            cart.route.clear();
+            continue;
+        }
+
+        let target = coord_to_translation(&Coord(next)).extend(transform.translation.z);
+
+        sprite.flip_x = target.x < transform.translation.x;
+
+        let underfoot = translation_to_coord(&transform.translation.xy());
+        let speed_multiplier =
+            surface_speed_multiplier(&underfoot, &constructions, &path_segments, &path_defs);
+
+        transform.translation = transform.translation.move_towards(
+            target,
+            cart_def.speed * speed_multiplier * time.delta_secs(),
+        );
+
+        if transform.translation == target {
+            cart.route.remove(0);
+        }
+    }
+}
+
+/// Moves one stack per tick between a docked cart and its station, unloading before loading.
+/// Items the stop unloads are never loaded there, so the cart can't pick its cargo straight back
+/// up. Once there is nothing left to move the cart sets off for its next stop.
+fn work_docked_carts(
+    carts: Query<(Entity, &mut Cart, &CartSchedule)>,
+    cart_defs: Res<Assets<CartDef>>,
+    inventory: Query<&Inventory>,
+    stacks: Query<&ItemStack>,
+    drop_offs: Query<&DropOff>,
+    pickups: Query<(), With<Pickup>>,
+    item_defs: Res<Assets<ItemDef>>,
+    mut transfer_items: MessageWriter<TransferItems>,
+    mut commands: Commands,
+) {
+    for (entity, mut cart, schedule) in carts {
+        if cart.state != CartState::Docked {
+            continue;
+        }
+
+        let Some(stop) = schedule.stops.get(cart.stop) else {
+            cart.state = CartState::Idle;
+            continue;
+        };
+
+        let Some(cart_def) = cart_defs.get(&cart.def) else {
+            continue;
+        };
+
+        let cart_slots: Vec<(Entity, &ItemStack)> = inventory
+            .iter_descendants(entity)
+            .filter_map(|slot| stacks.get(slot).ok().map(|stack| (slot, stack)))
+            .collect();
+
+        let station_slots: Vec<Entity> = inventory.iter_descendants(stop.station).collect();
+
+        let unload = cart_slots
+            .iter()
+            .filter(|(_, stack)| stack.quantity > 0 && stop.unload.admits(&stack.item, &item_defs))
+            .find_map(|(from_slot, stack)| {
+                station_slots.iter().find_map(|to_slot| {
+                    let accepts = drop_offs
+                        .get(*to_slot)
+                        .is_ok_and(|drop_off| drop_off.accepts(&stack.item, &item_defs));
+
+                    let room = slot_room(*to_slot, &stack.item, &stacks, &item_defs);
+
+                    (accepts && room > 0).then(|| TransferItems {
+                        from_slot: *from_slot,
+                        to_slot: *to_slot,
+                        quantity: stack.quantity.min(room),
+                    })
+                })
+            });
+
+        if let Some(transfer) = unload {
+            transfer_items.write(transfer);
+            continue;
+        }
+
+        let carried: u32 = cart_slots.iter().map(|(_, stack)| stack.quantity).sum();
+        let room = cart_def.capacity.saturating_sub(carried);
+
+        let load = station_slots
+            .iter()
+            .filter(|slot| pickups.contains(**slot))
+            .filter_map(|slot| stacks.get(*slot).ok().map(|stack| (*slot, stack)))
+            .find(|(_, stack)| {
+                stack.quantity > 0
+                    && stop.load.admits(&stack.item, &item_defs)
+                    && !stop.unload.admits(&stack.item, &item_defs)
+            });
+
+        if room > 0
+            && let Some((from_slot, stack)) = load
+        {
+            let to_slot = cart_slots
+                .iter()
+                .find(|(_, carried)| carried.item == stack.item)
+                .or_else(|| cart_slots.iter().find(|(_, carried)| carried.quantity == 0))
+                .map(|(slot, _)| *slot)
+                .unwrap_or_else(|| {
+                    commands
+                        .spawn(item_stack_slot(entity, stack.item.clone(), 0))
+                        .id()
+                });
+
+            transfer_items.write(TransferItems {
+                from_slot,
+                to_slot,
+                quantity: stack.quantity.min(room),
+            });
+            continue;
+        }
+
+        cart.stop = (cart.stop + 1) % schedule.stops.len();
+        cart.state = CartState::Travelling;
+    }
+}
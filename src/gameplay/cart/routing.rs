@@ -0,0 +1,94 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::gameplay::world::tilemap::CARDINALS;
+
+/// Shortest walk from `start` to the first tile satisfying `is_goal`, moving only across tiles
+/// satisfying `is_walkable`. The returned tiles exclude `start`, so an empty route means the
+/// start tile is itself a goal.
+pub fn shortest_route(
+    start: IVec2,
+    is_goal: impl Fn(&IVec2) -> bool,
+    is_walkable: impl Fn(&IVec2) -> bool,
+) -> Option<Vec<IVec2>> {
+    let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+    let mut frontier = VecDeque::from([start]);
+
+    while let Some(coord) = frontier.pop_front() {
+        if is_goal(&coord) {
+            let mut route = vec![];
+            let mut step = coord;
+
+            while step != start {
+                route.push(step);
+                step = came_from[&step];
+            }
+
+            route.reverse();
+
+            return Some(route);
+        }
+
+        for neighbor in CARDINALS.iter().map(|c| coord + c) {
+            if neighbor != start && is_walkable(&neighbor) && !came_from.contains_key(&neighbor) {
+                came_from.insert(neighbor, coord);
+                frontier.push_back(neighbor);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_route_to_start_is_empty() {
+        let route = shortest_route(IVec2::ZERO, |c| *c == IVec2::ZERO, |_| false);
+
+        assert_eq!(route, Some(vec![]));
+    }
+
+    #[test]
+    fn test_route_takes_shortest_branch() {
+        // Long way round to the north, short way along the x axis
+        let walkable = HashSet::from([
+            IVec2::new(0, 1),
+            IVec2::new(1, 1),
+            IVec2::new(2, 1),
+            IVec2::new(3, 1),
+            IVec2::new(3, 0),
+            IVec2::new(1, 0),
+            IVec2::new(2, 0),
+        ]);
+
+        let route = shortest_route(
+            IVec2::ZERO,
+            |c| *c == IVec2::new(3, 0),
+            |c| walkable.contains(c),
+        );
+
+        assert_eq!(
+            route,
+            Some(vec![IVec2::new(1, 0), IVec2::new(2, 0), IVec2::new(3, 0)])
+        );
+    }
+
+    #[test]
+    fn test_unreachable_goal_has_no_route() {
+        let walkable = HashSet::from([IVec2::new(1, 0)]);
+
+        let route = shortest_route(
+            IVec2::ZERO,
+            |c| *c == IVec2::new(5, 5),
+            |c| walkable.contains(c),
+        );
+
+        assert_eq!(route, None);
+    }
+}
@@ -5,12 +5,16 @@ use bevy::{
 use bevy_aseprite_ultra::prelude::*;
 
 use crate::{
-    gameplay::{FactorySystems, structure::assets::StructureDef, world::tilemap::TileClicked},
+    gameplay::{
+        FactorySystems,
+        structure::{assets::StructureDef, path::PathDef},
+        world::tilemap::TileClicked,
+    },
     input::input_map::{Action, InputActions},
     screens::Screen,
 };
 
-const HOTBAR_ACTIONS: [Action; 9] = [
+const HOTBAR_ACTIONS: [Action; 10] = [
     Action::Hotbar1,
     Action::Hotbar2,
     Action::Hotbar3,
@@ -20,6 +24,7 @@ const HOTBAR_ACTIONS: [Action; 9] = [
     Action::Hotbar7,
     Action::Hotbar8,
     Action::Hotbar9,
+    Action::Hotbar10,
 ];
 
 pub fn plugin(app: &mut App) {
@@ -116,7 +121,7 @@ struct HotbarActionOf(pub Entity);
 #[reflect(Component)]
 pub enum HotbarActionKind {
     PlaceStructure(Handle<StructureDef>),
-    PlacePath,
+    PlacePath(Handle<PathDef>),
 }
 
 #[derive(Component, Reflect, Debug)]
@@ -171,7 +176,12 @@ fn assign_hotbar_structures(
     structure_defs: Res<Assets<StructureDef>>,
     query: Query<Entity, (With<HotbarShortcut>, Without<Children>)>,
 ) {
-    for (hotbar_slot, (asset_id, structure_def)) in query.iter().zip(structure_defs.iter()) {
+    let mut structures: Vec<_> = structure_defs.iter().collect();
+    structures.sort_by(|(_, a), (_, b)| a.id.cmp(&b.id));
+
+    warn_unassigned(structures.len(), query.iter().len());
+
+    for (hotbar_slot, (asset_id, structure_def)) in query.iter().zip(structures) {
         commands.spawn((
             Name::new("Hotbar Action"),
             ChildOf(hotbar_slot),
@@ -182,8 +192,7 @@ fn assign_hotbar_structures(
             children![(
                 ImageNode::default(),
                 AseAnimation {
-                    aseprite: asset_server
-                        .load(format!("sprites/structures/{}.aseprite", structure_def.id)),
+                    aseprite: asset_server.load(&structure_def.sprite),
                     animation: Animation::tag("work").with_speed(0.0),
                 },
                 Pickable::IGNORE,
@@ -195,29 +204,45 @@ fn assign_hotbar_structures(
 fn assign_hotbar_paths(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    path_defs: Res<Assets<PathDef>>,
     query: Query<Entity, (With<HotbarShortcut>, Without<Children>)>,
 ) {
-    let Some(hotbar_slot) = query.iter().next() else {
-        return;
-    };
+    let mut paths: Vec<_> = path_defs.iter().collect();
+    paths.sort_by(|(_, a), (_, b)| a.id.cmp(&b.id));
 
-    commands.spawn((
-        Name::new("Hotbar Action"),
-        ChildOf(hotbar_slot),
-        HotbarActionOf(hotbar_slot),
-        HotbarActionKind::PlacePath,
-        HotbarPersistAfterUse,
-        Pickable::IGNORE,
-        Node::default(),
-        children![(
-            ImageNode::default(),
-            AseSlice {
-                aseprite: asset_server.load("sprites/logistics/path_segments.aseprite"),
-                name: "C".into(),
-            },
+    warn_unassigned(paths.len(), query.iter().len());
+
+    for (hotbar_slot, (asset_id, path_def)) in query.iter().zip(paths) {
+        commands.spawn((
+            Name::new("Hotbar Action"),
+            ChildOf(hotbar_slot),
+            HotbarActionOf(hotbar_slot),
+            HotbarActionKind::PlacePath(asset_server.get_id_handle(asset_id).unwrap()),
+            HotbarPersistAfterUse,
             Pickable::IGNORE,
-        )],
-    ));
+            Node::default(),
+            children![(
+                ImageNode {
+                    color: path_def.tint,
+                    ..default()
+                },
+                AseSlice {
+                    aseprite: asset_server.load(&path_def.sprite),
+                    name: "C".into(),
+                },
+                Pickable::IGNORE,
+            )],
+        ));
+    }
+}
+
+/// Constructs are assigned in order of their manifest id, those past the last free slot left out
+fn warn_unassigned(constructs: usize, free_slots: usize) {
+    if constructs > free_slots {
+        warn!(
+            "Only {free_slots} of {constructs} constructs fit on the hotbar, leaving out the rest"
+        );
+    }
 }
 
 fn highlight_selected_slot(
@@ -264,6 +289,7 @@ fn deselect_on_use(
     mut selector: HotbarSelector,
     hotbar_actions: Query<&HotbarAction>,
     persisted_actions: Query<Entity, With<HotbarPersistAfterUse>>,
+    input_actions: Res<InputActions>,
 ) {
     let Some(selected) = selector.selection.0 else {
         return;
@@ -273,7 +299,8 @@ fn deselect_on_use(
         return;
     };
 
-    if persisted_actions.contains(*action) {
+    if persisted_actions.contains(*action) || input_actions.pressed.contains(&Action::KeepSelection)
+    {
         return;
     }
 
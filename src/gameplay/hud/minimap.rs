@@ -0,0 +1,316 @@
+use std::{collections::HashMap, time::Duration};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    color::ColorToComponents,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    time::common_conditions::on_timer,
+};
+
+use crate::{
+    gameplay::{
+        structure::{
+            Structure, deposit::Deposit, obstacle::Obstacle, path::PathSegment, town::Town,
+        },
+        world::{
+            biome::Biomes,
+            construction::Constructions,
+            fog::{FogOfWar, tile_color},
+            generation::{ChunkPlan, Terrain, WorldGen},
+            logistics_overlay::LogisticsHeat,
+            tilemap::{
+                chunk::ChunkLoaded,
+                coord::{Coord, coord_to_translation, translation_to_coord},
+            },
+        },
+    },
+    screens::Screen,
+};
+
+/// Pixels of the minimap
+const MINIMAP_SIZE: UVec2 = UVec2::new(192, 192);
+
+/// World units covered by each pixel of the minimap, making a tile two pixels wide
+const MINIMAP_SCALE: f32 = 128.0;
+
+const MINIMAP_REFRESH: Duration = Duration::from_millis(100);
+
+const UNEXPLORED_COLOR: Color = Color::srgb(0.05, 0.05, 0.08);
+const GROUND_COLOR: Color = Color::srgb(0.45, 0.65, 0.35);
+const WATER_COLOR: Color = Color::srgb(0.2, 0.4, 0.85);
+const PATH_COLOR: Color = Color::srgb(0.7, 0.55, 0.35);
+const STRUCTURE_COLOR: Color = Color::srgb(0.85, 0.85, 0.85);
+const DEPOSIT_COLOR: Color = Color::srgb(0.95, 0.8, 0.2);
+const OBSTACLE_COLOR: Color = Color::srgb(0.3, 0.3, 0.3);
+const TOWN_COLOR: Color = Color::srgb(0.9, 0.45, 0.2);
+const VIEWPORT_COLOR: Color = Color::WHITE;
+const ATTENTION_COLOR: Color = Color::hsl(0.0, 1.0, 0.5);
+
+/// Pixels from the centre of an attention marker to its edge
+const MARKER_RADIUS: i32 = 2;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<ExploredChunks>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_explored_chunks, spawn_minimap),
+    );
+
+    app.add_observer(record_explored_chunk);
+    app.add_observer(pan_on_click);
+
+    app.add_systems(
+        Update,
+        draw_minimap.run_if(in_state(Screen::Gameplay).and(on_timer(MINIMAP_REFRESH))),
+    );
+}
+
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+struct Minimap;
+
+/// Ground colour of every tile of the chunks loaded so far, by chunk, kept after they unload so
+/// the minimap still shows them
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct ExploredChunks(HashMap<IVec2, Vec<Color>>);
+
+fn reset_explored_chunks(mut explored: ResMut<ExploredChunks>) {
+    *explored = ExploredChunks::default();
+}
+
+fn spawn_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let image = Image::new_fill(
+        Extent3d {
+            width: MINIMAP_SIZE.x,
+            height: MINIMAP_SIZE.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &UNEXPLORED_COLOR.to_srgba().to_u8_array(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+
+    commands.spawn((
+        Name::new("Minimap"),
+        DespawnOnExit(Screen::Gameplay),
+        Minimap,
+        Node {
+            position_type: PositionType::Absolute,
+            top: px(8.0),
+            right: px(8.0),
+            width: px(MINIMAP_SIZE.x as f32),
+            height: px(MINIMAP_SIZE.y as f32),
+            border: px(2.0).all(),
+            ..default()
+        },
+        BorderColor::all(Color::WHITE),
+        ImageNode::new(images.add(image)),
+        Pickable::default(),
+    ));
+}
+
+/// Remembers the ground of each chunk as it loads
+fn record_explored_chunk(
+    chunk_loaded: On<ChunkLoaded>,
+    plans: Query<&ChunkPlan>,
+    biomes: Biomes,
+    mut explored: ResMut<ExploredChunks>,
+) {
+    let Ok(plan) = plans.get(chunk_loaded.chunk) else {
+        return;
+    };
+
+    if explored.0.contains_key(&plan.chunk) {
+        return;
+    }
+
+    let ground = plan
+        .iter()
+        .map(|(_, _, tile)| {
+            let biome = tile.biome.and_then(|biome| biomes.by_index(biome));
+
+            match (tile.terrain, biome) {
+                (Terrain::River, _) => WATER_COLOR,
+                (_, Some(biome)) => tinted(GROUND_COLOR, biome.tint),
+                (_, None) => UNEXPLORED_COLOR,
+            }
+        })
+        .collect();
+
+    explored.0.insert(plan.chunk, ground);
+}
+
+fn tinted(color: Color, tint: Color) -> Color {
+    Srgba::from_vec4(color.to_srgba().to_vec4() * tint.to_srgba().to_vec4()).into()
+}
+
+/// Translation at the middle of a pixel of the minimap centred on `centre`
+fn pixel_to_translation(pixel: UVec2, centre: Vec2) -> Vec2 {
+    let offset = pixel.as_vec2() + Vec2::splat(0.5) - MINIMAP_SIZE.as_vec2() * 0.5;
+
+    // Rows of the image run downwards while the world's y axis points up
+    centre + Vec2::new(offset.x, -offset.y) * MINIMAP_SCALE
+}
+
+/// Pixel of the minimap centred on `centre` covering the translation, which may lie outside
+/// the minimap
+fn translation_to_pixel(translation: Vec2, centre: Vec2) -> IVec2 {
+    let offset = (translation - centre) / MINIMAP_SCALE;
+
+    (Vec2::new(offset.x, -offset.y) + MINIMAP_SIZE.as_vec2() * 0.5)
+        .floor()
+        .as_ivec2()
+}
+
+/// Draws the explored chunks around the camera with the constructs on their revealed tiles, the
+/// camera's view, and markers on structures whose porters recently got lost or had nowhere to go
+fn draw_minimap(
+    minimap: Single<&ImageNode, With<Minimap>>,
+    mut images: ResMut<Assets<Image>>,
+    camera: Single<(&Camera, &Transform, &Projection)>,
+    explored: Res<ExploredChunks>,
+    world_gen: Res<WorldGen>,
+    fog: Res<FogOfWar>,
+    constructions: Res<Constructions>,
+    constructs: Query<(
+        Has<Structure>,
+        Has<PathSegment>,
+        Has<Deposit>,
+        Has<Obstacle>,
+        Has<Town>,
+    )>,
+    heat: Res<LogisticsHeat>,
+    coords: Query<&Coord>,
+) {
+    let Some(image) = images.get_mut(&minimap.image) else {
+        return;
+    };
+
+    let (camera, camera_transform, projection) = *camera;
+    let centre = camera_transform.translation.xy();
+
+    let mut pixels = vec![UNEXPLORED_COLOR; (MINIMAP_SIZE.x * MINIMAP_SIZE.y) as usize];
+
+    for y in 0..MINIMAP_SIZE.y {
+        for x in 0..MINIMAP_SIZE.x {
+            let coord = translation_to_coord(&pixel_to_translation(UVec2::new(x, y), centre)).0;
+            let (chunk, local) = world_gen.locate(coord);
+
+            let Some(ground) = explored.0.get(&chunk) else {
+                continue;
+            };
+
+            let revealed = fog.is_revealed(coord);
+            let construct = constructions
+                .get(&coord)
+                .filter(|_| revealed)
+                .and_then(|entity| constructs.get(*entity).ok());
+
+            let color = match construct {
+                Some((true, ..)) => STRUCTURE_COLOR,
+                Some((_, true, ..)) => PATH_COLOR,
+                Some((_, _, true, ..)) => DEPOSIT_COLOR,
+                Some((.., true, _)) => OBSTACLE_COLOR,
+                Some((.., true)) => TOWN_COLOR,
+                _ => tile_color(
+                    ground[(local.y * world_gen.config.chunk_size.x + local.x) as usize],
+                    revealed,
+                ),
+            };
+
+            pixels[(y * MINIMAP_SIZE.x + x) as usize] = color;
+        }
+    }
+
+    let mut plot = |pixel: IVec2, color: Color| {
+        let pixel = pixel.clamp(IVec2::ZERO, MINIMAP_SIZE.as_ivec2() - IVec2::ONE);
+        pixels[(pixel.y as u32 * MINIMAP_SIZE.x + pixel.x as u32) as usize] = color;
+    };
+
+    if let (Some(viewport), Projection::Orthographic(ortho)) =
+        (camera.logical_viewport_size(), projection)
+    {
+        let half_extent = viewport * ortho.scale * 0.5;
+        let min = translation_to_pixel(centre + Vec2::new(-half_extent.x, half_extent.y), centre);
+        let max = translation_to_pixel(centre + Vec2::new(half_extent.x, -half_extent.y), centre);
+
+        for x in min.x..=max.x {
+            plot(IVec2::new(x, min.y), VIEWPORT_COLOR);
+            plot(IVec2::new(x, max.y), VIEWPORT_COLOR);
+        }
+
+        for y in min.y..=max.y {
+            plot(IVec2::new(min.x, y), VIEWPORT_COLOR);
+            plot(IVec2::new(max.x, y), VIEWPORT_COLOR);
+        }
+    }
+
+    // Markers beyond the edge are kept at the edge, pointing the way to structures out of view
+    let needing_attention = heat.losses.iter().chain(heat.unreachable.iter());
+    for (_, structure) in needing_attention {
+        let Ok(coord) = coords.get(*structure) else {
+            continue;
+        };
+
+        let marker = translation_to_pixel(coord_to_translation(coord), centre);
+
+        for y in -MARKER_RADIUS..=MARKER_RADIUS {
+            for x in -MARKER_RADIUS..=MARKER_RADIUS {
+                plot(marker + IVec2::new(x, y), ATTENTION_COLOR);
+            }
+        }
+    }
+
+    image.data = Some(
+        pixels
+            .iter()
+            .flat_map(|color| color.to_srgba().to_u8_array())
+            .collect(),
+    );
+}
+
+/// Moves the camera to the spot of the world clicked on the minimap
+fn pan_on_click(
+    click: On<Pointer<Click>>,
+    minimap: Query<(), With<Minimap>>,
+    mut camera_transform: Single<&mut Transform, With<Camera>>,
+) {
+    if click.button != PointerButton::Primary || !minimap.contains(click.entity) {
+        return;
+    }
+
+    // Relative to the minimap's centre, from -0.5 to 0.5 across it, rows running downwards
+    let Some(position) = click.hit.position else {
+        return;
+    };
+
+    let offset = position.xy() * MINIMAP_SIZE.as_vec2() * MINIMAP_SCALE;
+    camera_transform.translation += Vec3::new(offset.x, -offset.y, 0.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixels_map_to_translations_and_back() {
+        let centre = Vec2::new(1000.0, -300.0);
+
+        for pixel in [UVec2::ZERO, MINIMAP_SIZE / 2, MINIMAP_SIZE - UVec2::ONE] {
+            let translation = pixel_to_translation(pixel, centre);
+            assert_eq!(translation_to_pixel(translation, centre), pixel.as_ivec2());
+        }
+
+        assert_eq!(
+            translation_to_pixel(centre, centre),
+            (MINIMAP_SIZE / 2).as_ivec2()
+        );
+
+        // The top row of the minimap shows what lies above the camera's view
+        assert!(pixel_to_translation(UVec2::ZERO, centre).y > centre.y);
+    }
+}
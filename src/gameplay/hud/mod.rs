@@ -4,9 +4,10 @@ use bevy_aseprite_ultra::prelude::*;
 use crate::screens::Screen;
 
 pub mod hotbar;
+pub mod minimap;
 
 pub fn plugin(app: &mut App) {
-    app.add_plugins(hotbar::plugin);
+    app.add_plugins((hotbar::plugin, minimap::plugin));
 
     app.add_systems(OnEnter(Screen::Gameplay), (setup_portrait, setup_relic));
 }
@@ -23,6 +23,10 @@ pub enum ItemTag {
     Rotting,
 }
 
+impl ItemTag {
+    pub const ALL: [ItemTag; 1] = [ItemTag::Rotting];
+}
+
 #[derive(Asset, Clone, Debug, Deserialize, Reflect)]
 pub struct ItemRaw {
     pub id: String,
@@ -40,13 +40,58 @@ pub struct Output {
 }
 
 /// Marks slot for porter drop off
-#[derive(Component, Reflect)]
+#[derive(Component, Reflect, Debug, PartialEq)]
 #[reflect(Component)]
 pub enum DropOff {
     /// Matches exact item
     Item(Handle<ItemDef>),
     /// Matches item with tag
     Tag(ItemTag),
+    /// Matches item passing any of the filters, or any item when there are none
+    Filter(Vec<ItemFilter>),
+}
+
+impl DropOff {
+    /// Whether the slot accepts the given item
+    pub fn accepts(&self, item: &Handle<ItemDef>, item_defs: &Assets<ItemDef>) -> bool {
+        match self {
+            DropOff::Item(handle) => handle == item,
+            DropOff::Tag(tag) => item_defs
+                .get(item)
+                .is_some_and(|item_def| item_def.tags.contains(tag)),
+            DropOff::Filter(filters) => {
+                filters.is_empty() || filters.iter().any(|filter| filter.matches(item, item_defs))
+            }
+        }
+    }
+}
+
+/// Preference of porters for a drop-off slot. Porters deliver to the highest priority slot in
+/// reach, picking at random between slots of equal priority.
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[reflect(Component)]
+pub struct DropOffPriority(pub i32);
+
+/// Player-set rule selecting which items a slot takes
+#[derive(Reflect, Clone, Debug, PartialEq)]
+pub enum ItemFilter {
+    Item(Handle<ItemDef>),
+    Tag(ItemTag),
+    Taxonomy(Taxonomy),
+}
+
+impl ItemFilter {
+    pub fn matches(&self, item: &Handle<ItemDef>, item_defs: &Assets<ItemDef>) -> bool {
+        match self {
+            ItemFilter::Item(handle) => handle == item,
+            ItemFilter::Tag(tag) => item_defs
+                .get(item)
+                .is_some_and(|item_def| item_def.tags.contains(tag)),
+            ItemFilter::Taxonomy(taxonomy) => item_defs
+                .get(item)
+                .is_some_and(|item_def| item_def.taxonomy == *taxonomy),
+        }
+    }
 }
 
 /// Marks slot for porter pickup
@@ -63,6 +108,10 @@ pub enum Taxonomy {
     Minerale,
 }
 
+impl Taxonomy {
+    pub const ALL: [Taxonomy; 3] = [Taxonomy::Fauna, Taxonomy::Flora, Taxonomy::Minerale];
+}
+
 /// Defines which transport method is used for an item
 #[derive(Clone, Debug, Deserialize, Reflect)]
 pub enum Transport {
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 
 use super::prelude::*;
@@ -20,6 +22,9 @@ pub(super) fn transfer_items(
     mut stacks: Query<&mut ItemStack>,
     mut commands: Commands,
 ) {
+    // Stacks created in slots that had none, gathered so transfers into the same slot add up
+    let mut created: HashMap<Entity, ItemStack> = HashMap::new();
+
     for TransferItems {
         from_slot,
         to_slot,
@@ -27,6 +32,10 @@ pub(super) fn transfer_items(
     } in transfer_items.read()
     {
         if let Ok([mut from_stack, mut to_stack]) = stacks.get_many_mut([*from_slot, *to_slot]) {
+            if from_stack.item != to_stack.item && to_stack.quantity == 0 {
+                to_stack.item = from_stack.item.clone();
+            }
+
             if from_stack.item != to_stack.item {
                 warn!(
                     "Attempted to transfer items between incompatible stacks: {from_slot} -> {to_slot}"
@@ -38,17 +47,29 @@ pub(super) fn transfer_items(
             from_stack.quantity = from_stack.quantity.saturating_sub(actual_quantity);
             to_stack.quantity = to_stack.quantity.saturating_add(actual_quantity);
 
-            return;
+            continue;
         }
 
         if let Ok(mut from_stack) = stacks.get_mut(*from_slot) {
-            let actual_quantity = from_stack.quantity.min(*quantity);
-            from_stack.quantity = from_stack.quantity.saturating_sub(actual_quantity);
-
-            commands.entity(*to_slot).insert(ItemStack {
+            let to_stack = created.entry(*to_slot).or_insert_with(|| ItemStack {
                 item: from_stack.item.clone(),
-                quantity: actual_quantity,
+                quantity: 0,
             });
+
+            if from_stack.item != to_stack.item {
+                warn!(
+                    "Attempted to transfer items between incompatible stacks: {from_slot} -> {to_slot}"
+                );
+                continue;
+            }
+
+            let actual_quantity = from_stack.quantity.min(*quantity);
+            from_stack.quantity = from_stack.quantity.saturating_sub(actual_quantity);
+            to_stack.quantity = to_stack.quantity.saturating_add(actual_quantity);
         }
     }
+
+    for (slot, stack) in created {
+        commands.entity(slot).insert(stack);
+    }
 }
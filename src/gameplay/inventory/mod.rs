@@ -12,11 +12,12 @@ pub mod prelude {
 
     pub use assets::{ItemDef, ItemTag};
     pub use components::{
-        DropOff, InInventory, Input, Inventory, ItemStack, Output, Pickup, Taxonomy, Transport,
+        DropOff, DropOffPriority, InInventory, Input, Inventory, ItemFilter, ItemStack, Output,
+        Pickup, Taxonomy, Transport,
     };
     pub use messages::{ItemTransferSystems, TransferItems};
     pub use prefabs::{empty_slot, item_stack_slot};
-    pub use utils::{can_afford, refund, spend};
+    pub use utils::{can_afford, missing_cost, refund, slot_room, spend};
 }
 
 pub fn plugin(app: &mut App) {
@@ -12,8 +12,18 @@ pub fn can_afford(
     // Because both functions are used from the same system, they could only have 1 access level
     stacks: &Query<&mut ItemStack>,
 ) -> bool {
+    missing_cost(entity, cost, inventory, stacks).is_empty()
+}
+
+/// Quantities of each item in the cost the entity is short of
+pub fn missing_cost(
+    entity: Entity,
+    cost: &HashMap<Handle<ItemDef>, u32>,
+    inventory: &Query<&Inventory>,
+    stacks: &Query<&mut ItemStack>,
+) -> HashMap<Handle<ItemDef>, u32> {
     if cost.is_empty() {
-        return true;
+        return HashMap::new();
     }
 
     let mut available: HashMap<AssetId<ItemDef>, u32> = HashMap::new();
@@ -28,15 +38,18 @@ pub fn can_afford(
             .or_insert(item_stack.quantity);
     }
 
-    for (required_item, required_quantity) in cost {
-        let available_quantity = available.get(&required_item.id()).copied().unwrap_or(0);
+    cost.iter()
+        .filter_map(|(required_item, required_quantity)| {
+            let available_quantity = available.get(&required_item.id()).copied().unwrap_or(0);
 
-        if available_quantity < *required_quantity {
-            return false;
-        }
-    }
-
-    true
+            (available_quantity < *required_quantity).then(|| {
+                (
+                    required_item.clone(),
+                    required_quantity - available_quantity,
+                )
+            })
+        })
+        .collect()
 }
 
 pub fn spend(
@@ -79,3 +92,22 @@ pub fn refund(
         }
     }
 }
+
+/// How many more of `item` fit in the slot
+pub fn slot_room(
+    slot: Entity,
+    item: &Handle<ItemDef>,
+    stacks: &Query<&ItemStack>,
+    item_defs: &Assets<ItemDef>,
+) -> u32 {
+    let Some(item_def) = item_defs.get(item) else {
+        return 0;
+    };
+
+    match stacks.get(slot) {
+        Ok(stack) if stack.quantity == 0 => item_def.stack_size,
+        Ok(stack) if stack.item == *item => item_def.stack_size.saturating_sub(stack.quantity),
+        Ok(_) => 0,
+        Err(_) => item_def.stack_size,
+    }
+}
@@ -6,6 +6,8 @@ use bevy::{
 
 use crate::screens::Screen;
 
+pub mod blueprint;
+pub mod cart;
 pub mod hud;
 pub mod inventory;
 pub mod people;
@@ -34,6 +36,8 @@ pub fn plugin(app: &mut App) {
     );
 
     app.add_plugins((
+        blueprint::plugin,
+        cart::plugin,
         hud::plugin,
         inventory::plugin,
         people::plugin,
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+
+use crate::{
+    gameplay::{
+        inventory::prelude::*,
+        people::porting::{Porting, PortingState},
+    },
+    widgets::tooltip::{HideTooltip, ShowTooltip, TooltipSource},
+};
+
+const ICON_SIZE: f32 = 8.0;
+const ICON_OFFSET: Vec3 = Vec3::new(0.0, 20.0, 1.0);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<HoveredPorter>();
+
+    app.add_observer(on_porter_hover);
+    app.add_observer(on_porter_leave);
+
+    app.add_systems(
+        Update,
+        (
+            spawn_carried_item_icons,
+            refresh_carried_item_icons,
+            refresh_porter_tooltip,
+        )
+            .chain(),
+    );
+}
+
+/// World-space icon of the item a porter is carrying
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct CarriedItemIcon;
+
+/// Stack count shown next to the carried item icon when carrying more than one
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct CarriedItemCount;
+
+/// Porter under the pointer, with the tooltip last shown for it
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct HoveredPorter {
+    porter: Option<Entity>,
+    tooltip: Option<String>,
+}
+
+fn spawn_carried_item_icons(
+    porters: Query<Entity, Added<Porting>>,
+    children: Query<&Children>,
+    icons: Query<(), With<CarriedItemIcon>>,
+    mut commands: Commands,
+) {
+    for porter in porters {
+        if children
+            .iter_descendants(porter)
+            .any(|child| icons.contains(child))
+        {
+            continue;
+        }
+
+        commands.spawn((
+            Name::new("Carried Item"),
+            CarriedItemIcon,
+            Sprite::sized(Vec2::splat(ICON_SIZE)),
+            Transform::from_translation(ICON_OFFSET),
+            Visibility::Hidden,
+            Pickable::IGNORE,
+            ChildOf(porter),
+            children![(
+                CarriedItemCount,
+                Text2d::default(),
+                TextFont::default().with_font_size(8.0),
+                Transform::from_xyz(ICON_SIZE, -ICON_SIZE / 2.0, 0.0),
+                Pickable::IGNORE,
+            )],
+        ));
+    }
+}
+
+fn refresh_carried_item_icons(
+    mut icons: Query<(Entity, &ChildOf, &mut Sprite, &mut Visibility), With<CarriedItemIcon>>,
+    mut counts: Query<&mut Text2d, With<CarriedItemCount>>,
+    porters: Query<(), With<Porting>>,
+    children: Query<&Children>,
+    inventory: Query<&Inventory>,
+    stacks: Query<&ItemStack>,
+    item_defs: Res<Assets<ItemDef>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for (icon, ChildOf(porter), mut sprite, mut visibility) in &mut icons {
+        let carried = inventory
+            .iter_descendants(*porter)
+            .filter_map(|slot| stacks.get(slot).ok())
+            .find(|stack| stack.quantity > 0)
+            .filter(|_| porters.contains(*porter));
+
+        let Some(stack) = carried else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        let Some(item_def) = item_defs.get(&stack.item) else {
+            continue;
+        };
+
+        if let Some(image) = images.get_strong_handle(item_def.sprite) {
+            sprite.image = image;
+        }
+
+        *visibility = Visibility::Inherited;
+
+        for child in children.iter_descendants(icon) {
+            if let Ok(mut text) = counts.get_mut(child) {
+                text.0 = match stack.quantity {
+                    1 => String::new(),
+                    quantity => quantity.to_string(),
+                };
+            }
+        }
+    }
+}
+
+fn on_porter_hover(
+    pointer_over: On<Pointer<Over>>,
+    porters: Query<(), With<Porting>>,
+    mut hovered_porter: ResMut<HoveredPorter>,
+) {
+    if porters.contains(pointer_over.entity) {
+        *hovered_porter = HoveredPorter {
+            porter: Some(pointer_over.entity),
+            tooltip: None,
+        };
+    }
+}
+
+fn on_porter_leave(
+    pointer_out: On<Pointer<Out>>,
+    mut hovered_porter: ResMut<HoveredPorter>,
+    mut commands: Commands,
+) {
+    if hovered_porter.porter == Some(pointer_out.entity) {
+        *hovered_porter = HoveredPorter::default();
+        commands.trigger(HideTooltip(TooltipSource::Porter));
+    }
+}
+
+fn refresh_porter_tooltip(
+    mut hovered_porter: ResMut<HoveredPorter>,
+    porters: Query<&Porting>,
+    names: Query<&Name>,
+    item_defs: Res<Assets<ItemDef>>,
+    mut commands: Commands,
+) {
+    let Some(porter) = hovered_porter.porter else {
+        return;
+    };
+
+    // The porter finished its trip while hovered
+    let Ok(porting) = porters.get(porter) else {
+        *hovered_porter = HoveredPorter::default();
+        commands.trigger(HideTooltip(TooltipSource::Porter));
+        return;
+    };
+
+    let name_of = |entity: Entity| {
+        names
+            .get(entity)
+            .map_or(String::from("Unknown"), |name| name.to_string())
+    };
+
+    let item = item_defs
+        .get(&porting.item)
+        .map_or(String::from("item"), |item_def| item_def.name.clone());
+
+    let origin = name_of(porting.origin);
+
+    let destination = match porting.state {
+        PortingState::PickingUpItems | PortingState::TransportTo => {
+            format!("Any drop-off for {item}")
+        }
+        PortingState::DroppingOffItems => name_of(porting.target),
+        PortingState::Returnal => origin.clone(),
+    };
+
+    let text = [
+        name_of(porter),
+        format!("Origin: {origin}"),
+        format!("Destination: {destination}"),
+        format!("Patience: {}s", porting.ttl.as_secs()),
+    ]
+    .join("\n");
+
+    if hovered_porter.tooltip.as_ref() == Some(&text) {
+        return;
+    }
+
+    hovered_porter.tooltip = Some(text.clone());
+    commands.trigger(ShowTooltip {
+        source: TooltipSource::Porter,
+        text,
+    });
+}
@@ -3,7 +3,11 @@ use bevy::prelude::*;
 use crate::gameplay::{
     inventory::prelude::*,
     people::{Assignment, Forager, Person, profession::ProfessionSystems},
-    structure::{deposit::Deposit, foragers_outpost::ForagersOutpost, range::Range},
+    structure::{
+        deposit::{Deposit, Regrowth},
+        foragers_outpost::ForagersOutpost,
+        range::Range,
+    },
     world::{construction::Constructions, tilemap::coord::Coord},
 };
 
@@ -97,8 +101,9 @@ fn forage_deposit(
     }
 }
 
+/// Removes emptied deposits, except renewable ones which regrow instead
 fn cleanup_empty_deposits(
-    deposits: Query<(Entity, &Coord), With<Deposit>>,
+    deposits: Query<(Entity, &Coord), (With<Deposit>, Without<Regrowth>)>,
     inventory: Query<&Inventory>,
     slots: Query<&ItemStack>,
     mut constructions: ResMut<Constructions>,
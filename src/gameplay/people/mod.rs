@@ -4,10 +4,12 @@ use crate::gameplay::{
     inventory::prelude::*, people::naming::NameManager, world::construction::StructureConstructed,
 };
 
+pub mod carried_item;
 pub mod foraging;
 pub mod naming;
 pub mod porting;
 pub mod profession;
+pub mod traffic;
 
 #[allow(unused_imports)]
 pub use profession::{
@@ -17,10 +19,12 @@ pub use profession::{
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
+        carried_item::plugin,
         foraging::plugin,
         naming::plugin,
         porting::plugin,
         profession::plugin,
+        traffic::plugin,
     ));
 
     app.add_systems(
@@ -6,12 +6,21 @@ use rand::seq::{IndexedRandom, IteratorRandom};
 
 use crate::gameplay::{
     inventory::prelude::*,
-    people::{Assignees, Person, Porter, profession::ProfessionSystems},
+    people::{Assignees, Person, Porter, profession::ProfessionSystems, traffic::Congestion},
     random::Seed,
     sprite_sort::{YSortSprite, ZIndexSprite},
+    structure::{
+        footprint::Footprint,
+        junction::Junction,
+        orientation::Faces,
+        path::{PathDef, PathSegment, surface_speed_multiplier},
+    },
     world::{
-        construction::Constructions,
-        tilemap::{CARDINALS, coord::Coord},
+        construction::{ConstructReplaced, Constructions},
+        tilemap::{
+            CARDINALS,
+            coord::{Coord, translation_to_coord},
+        },
     },
 };
 
@@ -34,6 +43,8 @@ pub(super) fn plugin(app: &mut App) {
         )
             .in_set(ProfessionSystems),
     );
+
+    app.add_observer(hand_over_replaced_construct);
 }
 
 #[derive(Component, Reflect, Deref, DerefMut)]
@@ -92,6 +103,8 @@ fn spawn_porter(
         Entity,
         &Transform,
         &Coord,
+        &Footprint,
+        &Faces,
         &mut PorterCooldown,
         &mut PorterSpawnOutputIndex,
         &Assignees,
@@ -107,7 +120,9 @@ fn spawn_porter(
     time: Res<Time>,
     mut seed: ResMut<Seed>,
 ) {
-    for (structure, transform, coord, mut timer, mut index, assignees) in structure_query {
+    for (structure, transform, coord, footprint, faces, mut timer, mut index, assignees) in
+        structure_query
+    {
         if !timer.tick(time.delta()).is_finished() {
             continue;
         }
@@ -118,7 +133,7 @@ fn spawn_porter(
 
         let Some(slot) = inventory
             .iter_descendants(structure)
-            .find(|e| pickup_stacks.contains(*e))
+            .find(|e| pickup_stacks.get(*e).is_ok_and(|stack| stack.quantity > 0))
         else {
             continue;
         };
@@ -135,10 +150,11 @@ fn spawn_porter(
             continue;
         };
 
-        let Some(neighbor) = CARDINALS
+        let Some(neighbor) = footprint
+            .neighbours(coord.0)
             .iter()
-            .map(|c| coord.0 + c)
-            .filter_map(|c| constructions.get(&c))
+            .filter(|c| faces.offers_output_to(coord.0, footprint, **c))
+            .filter_map(|c| constructions.get(c))
             .filter(|e| walkables.contains(**e))
             .choose(&mut seed)
         else {
@@ -158,6 +174,7 @@ fn spawn_porter(
             },
             YSortSprite,
             ZIndexSprite(10),
+            Congestion::default(),
             Porting {
                 item: stack.item.clone(),
                 origin: structure,
@@ -214,22 +231,40 @@ fn pickup_items(
     }
 }
 
-fn move_towards_target(
-    porters: Query<(Entity, &mut Transform, &mut Sprite, &Porting)>,
+pub(super) fn move_towards_target(
+    porters: Query<(
+        Entity,
+        &mut Transform,
+        &mut Sprite,
+        &Porting,
+        Option<&Congestion>,
+    )>,
     tiles: Query<&Transform, Without<Porting>>,
+    constructions: Res<Constructions>,
+    path_segments: Query<&PathSegment>,
+    path_defs: Res<Assets<PathDef>>,
     mut target_reached: MessageWriter<PorterCheckpointReached>,
     time: Res<Time>,
 ) {
-    for (porter, mut transform, mut sprite, porting) in porters {
+    for (porter, mut transform, mut sprite, porting, congestion) in porters {
         let Ok(target_transform) = tiles.get(porting.target) else {
             continue;
         };
 
         sprite.flip_x = target_transform.translation.x < transform.translation.x;
 
+        if congestion.is_some_and(|congestion| congestion.yielding) {
+            continue;
+        }
+
+        let underfoot = translation_to_coord(&transform.translation.xy());
+        let speed_multiplier =
+            surface_speed_multiplier(&underfoot, &constructions, &path_segments, &path_defs)
+                * congestion.map_or(1.0, |congestion| congestion.speed_factor);
+
         transform.translation = transform.translation.move_towards(
             target_transform.translation,
-            porting.speed * time.delta_secs(),
+            porting.speed * speed_multiplier * time.delta_secs(),
         );
 
         if transform
@@ -247,10 +282,12 @@ fn calculate_next_target(
     mut targets_reached: MessageReader<PorterCheckpointReached>,
     mut porters: Query<&mut Porting>,
     coords: Query<&Coord>,
+    structures: Query<(&Coord, &Footprint, &Faces)>,
     walkables: Query<(), With<Walkable>>,
     constructions: Res<Constructions>,
     inventory: Query<&Inventory>,
-    drop_off_slots: Query<&DropOff>,
+    drop_off_slots: Query<(&DropOff, Option<&DropOffPriority>)>,
+    mut junctions: Query<&mut Junction>,
     item_definitions: Res<Assets<ItemDef>>,
     mut porter_arrived: MessageWriter<PorterArrival>,
     mut seed: ResMut<Seed>,
@@ -279,24 +316,56 @@ fn calculate_next_target(
             .filter_map(|c| constructions.get(&c).cloned())
             .collect();
 
-        if let Some(slot) = neighbors
+        // Structures spanning several neighbouring tiles are only counted once
+        let mut seen = HashSet::new();
+
+        let candidates: Vec<(Entity, Entity, DropOffPriority)> = neighbors
             .iter()
-            .filter_map(|construction| {
-                inventory.iter_descendants(*construction).find(|slot| {
-                    let Ok(drop_off) = drop_off_slots.get(*slot) else {
-                        return false;
-                    };
-
-                    match drop_off {
-                        DropOff::Item(handle) => porting.item == *handle,
-                        DropOff::Tag(tag) => item_definitions
-                            .get(&porting.item)
-                            .is_some_and(|item| item.tags.contains(tag)),
-                    }
-                })
+            .filter(|construction| seen.insert(**construction))
+            .filter(|construction| **construction != porting.origin)
+            .filter(|construction| {
+                structures
+                    .get(**construction)
+                    .ok()
+                    .is_none_or(|(origin, footprint, faces)| {
+                        faces.accepts_input_from(origin.0, footprint, coord.0)
+                    })
             })
-            .choose(&mut seed)
-        {
+            .flat_map(|construction| {
+                inventory
+                    .iter_descendants(*construction)
+                    .map(move |slot| (*construction, slot))
+            })
+            .filter_map(|(construction, slot)| {
+                let (drop_off, priority) = drop_off_slots.get(slot).ok()?;
+
+                drop_off
+                    .accepts(&porting.item, &item_definitions)
+                    .then(|| (construction, slot, priority.copied().unwrap_or_default()))
+            })
+            .collect();
+
+        let highest_priority = candidates.iter().map(|(.., priority)| *priority).max();
+        let candidates: Vec<(Entity, Entity)> = candidates
+            .into_iter()
+            .filter(|(.., priority)| Some(*priority) == highest_priority)
+            .map(|(construction, slot, _)| (construction, slot))
+            .collect();
+
+        // Pick a structure first, so one with many open slots isn't favoured over its neighbours
+        let mut structures: Vec<Entity> = candidates
+            .iter()
+            .map(|(construction, _)| *construction)
+            .collect();
+        structures.dedup();
+
+        if let Some(slot) = structures.choose(&mut seed).and_then(|structure| {
+            candidates
+                .iter()
+                .filter(|(construction, _)| construction == structure)
+                .map(|(_, slot)| *slot)
+                .choose(&mut seed)
+        }) {
             porter_arrived.write(PorterArrival {
                 porter: *porter,
                 slot,
@@ -304,7 +373,7 @@ fn calculate_next_target(
             return;
         }
 
-        let paths: Vec<Entity> = neighbors
+        let mut paths: Vec<Entity> = neighbors
             .iter()
             .cloned()
             .filter(|e| {
@@ -312,6 +381,15 @@ fn calculate_next_target(
             })
             .collect();
 
+        if let Ok(mut junction) = junctions.get_mut(target) {
+            let branch = |e: &Entity| coords.get(*e).map(|c| c.0 - coord.0).ok();
+
+            let branches: Vec<IVec2> = paths.iter().filter_map(branch).collect();
+            let routed = junction.route(&porting.item, &item_definitions, &branches);
+
+            paths.retain(|e| branch(e).is_some_and(|b| routed.contains(&b)));
+        }
+
         if let Some(t) = paths.choose(&mut seed) {
             porting.target = *t;
             porting.backtracking = false;
@@ -322,7 +400,32 @@ fn calculate_next_target(
     }
 }
 
-fn despawn_lost_porters(
+/// Points porters heading for, or retracing their steps through, a replaced construct at its
+/// replacement, so they don't stall on a tile whose entity is gone
+fn hand_over_replaced_construct(replaced: On<ConstructReplaced>, porters: Query<&mut Porting>) {
+    let ConstructReplaced {
+        replaced,
+        replacement,
+    } = *replaced;
+
+    for mut porting in porters {
+        if porting.target == replaced {
+            porting.target = replacement;
+        }
+
+        for step in porting.path.iter_mut() {
+            if *step == replaced {
+                *step = replacement;
+            }
+        }
+
+        if porting.visited.remove(&replaced) {
+            porting.visited.insert(replacement);
+        }
+    }
+}
+
+pub fn despawn_lost_porters(
     mut porter_losses: MessageReader<PorterLost>,
     mut commands: Commands,
     porters: Query<&Porting>,
@@ -393,7 +496,7 @@ fn returnal(
     }
 }
 
-fn decrement_ttl(
+pub fn decrement_ttl(
     porters: Query<(Entity, &mut Porting)>,
     time: Res<Time>,
     mut porter_losses: MessageWriter<PorterLost>,
@@ -0,0 +1,372 @@
+use std::{collections::HashMap, ops::DerefMut, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    gameplay::{
+        people::{
+            porting::{Porting, move_towards_target},
+            profession::ProfessionSystems,
+        },
+        structure::path::{PathDef, PathSegment, tile_capacity},
+        world::{construction::Constructions, tilemap::coord::translation_to_coord},
+    },
+    input::input_map::{Action, action_just_pressed},
+};
+
+/// Length of the window porter throughput is measured over
+pub const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<TrafficSettings>();
+    app.init_resource::<TileTraffic>();
+
+    app.add_systems(
+        FixedUpdate,
+        regulate_traffic
+            .in_set(ProfessionSystems)
+            .before(move_towards_target),
+    );
+
+    app.add_systems(
+        Update,
+        toggle_traffic.run_if(action_just_pressed(Action::TrafficSimulation)),
+    );
+}
+
+/// Settings for the porter traffic simulation
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct TrafficSettings {
+    /// Whether porters respect the capacity of path tiles, off unless switched on. Throughput is
+    /// measured either way.
+    pub enabled: bool,
+}
+
+/// Porter traffic across the tiles of the world
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct TileTraffic {
+    /// Porters currently standing on each tile
+    pub occupancy: HashMap<IVec2, u32>,
+    /// Porters entering each tile per second, measured over the last completed window
+    pub throughput: HashMap<IVec2, f32>,
+    entries: HashMap<IVec2, u32>,
+    window: Timer,
+}
+
+impl Default for TileTraffic {
+    fn default() -> Self {
+        Self {
+            occupancy: HashMap::default(),
+            throughput: HashMap::default(),
+            entries: HashMap::default(),
+            window: Timer::new(THROUGHPUT_WINDOW, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Traffic state of a single porter
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Congestion {
+    /// Tile the porter stood on during the last tick
+    pub coord: Option<IVec2>,
+    /// Tile the porter was let onto, its place there held until it arrives
+    pub reserved: Option<IVec2>,
+    /// Whether the porter is queued, waiting for room on the tile it is heading to
+    pub yielding: bool,
+    /// Time spent queued, porters that have waited the longest get right of way
+    pub waiting: Duration,
+    /// Multiplier applied to movement speed while the current tile is overcrowded
+    pub speed_factor: f32,
+}
+
+impl Default for Congestion {
+    fn default() -> Self {
+        Self {
+            coord: None,
+            reserved: None,
+            yielding: false,
+            waiting: Duration::ZERO,
+            speed_factor: 1.0,
+        }
+    }
+}
+
+fn toggle_traffic(mut settings: ResMut<TrafficSettings>) {
+    settings.enabled = !settings.enabled;
+}
+
+/// Measures where porters stand and lets them onto the tile they are heading to while it has
+/// room, when the simulation is on
+fn regulate_traffic(
+    porters: Query<(Entity, &Transform, &Porting, &mut Congestion)>,
+    tiles: Query<&Transform, Without<Porting>>,
+    constructions: Res<Constructions>,
+    path_segments: Query<&PathSegment>,
+    path_defs: Res<Assets<PathDef>>,
+    settings: Res<TrafficSettings>,
+    mut traffic: ResMut<TileTraffic>,
+    time: Res<Time>,
+) {
+    let traffic = &mut *traffic;
+
+    traffic.occupancy.clear();
+
+    let mut movements = Vec::new();
+
+    for (porter, transform, porting, mut congestion) in porters {
+        let current = translation_to_coord(&transform.translation.xy()).0;
+
+        *traffic.occupancy.entry(current).or_default() += 1;
+
+        if congestion.coord != Some(current) {
+            *traffic.entries.entry(current).or_default() += 1;
+            congestion.coord = Some(current);
+        }
+
+        let target = tiles
+            .get(porting.target)
+            .ok()
+            .map(|target| translation_to_coord(&target.translation.xy()).0);
+
+        movements.push((porter, current, target, congestion));
+    }
+
+    if traffic.window.tick(time.delta()).just_finished() {
+        let window = traffic.window.duration().as_secs_f32();
+
+        traffic.throughput = traffic
+            .entries
+            .drain()
+            .map(|(coord, entries)| (coord, entries as f32 / window))
+            .collect();
+    }
+
+    if !settings.enabled {
+        for (.., mut congestion) in movements {
+            *congestion = Congestion {
+                coord: congestion.coord,
+                ..default()
+            };
+        }
+
+        return;
+    }
+
+    regulate(
+        &mut movements,
+        |coord| tile_capacity(coord, &constructions, &path_segments, &path_defs),
+        &traffic.occupancy,
+        time.delta(),
+    );
+}
+
+/// Queues porters, each given with the tile it stands on and the one it is heading to, until
+/// their next tile has room. A porter let through keeps its place on that tile reserved until it
+/// arrives, so nobody else is let into it meanwhile. Porters competing for the same tile, such as
+/// at intersections, are let through in order of how long they have waited.
+fn regulate<C: DerefMut<Target = Congestion>>(
+    movements: &mut [(Entity, IVec2, Option<IVec2>, C)],
+    capacity: impl Fn(&IVec2) -> Option<u32>,
+    occupancy: &HashMap<IVec2, u32>,
+    delta: Duration,
+) {
+    let occupancy_of = |coord: &IVec2| occupancy.get(coord).copied().unwrap_or_default();
+
+    let mut reserved: HashMap<IVec2, u32> = HashMap::new();
+    let mut requests = Vec::new();
+
+    for (porter, current, target, congestion) in movements.iter_mut() {
+        congestion.yielding = false;
+        congestion.speed_factor = 1.0;
+
+        if let Some(capacity) = capacity(current) {
+            let occupancy = occupancy_of(current);
+
+            if occupancy > capacity {
+                congestion.speed_factor = capacity as f32 / occupancy as f32;
+            }
+        }
+
+        // Reservations end once the porter arrives, or when it turns elsewhere
+        if congestion
+            .reserved
+            .is_some_and(|tile| tile == *current || Some(tile) != *target)
+        {
+            congestion.reserved = None;
+        }
+
+        match (congestion.reserved, *target) {
+            (Some(tile), _) => *reserved.entry(tile).or_default() += 1,
+            (None, Some(target)) if target != *current => {
+                requests.push((*porter, target, congestion.waiting));
+            }
+            _ => congestion.waiting = Duration::ZERO,
+        }
+    }
+
+    let admitted: HashMap<Entity, bool> = admit(requests, capacity, |coord| {
+        occupancy_of(coord) + reserved.get(coord).copied().unwrap_or_default()
+    })
+    .into_iter()
+    .collect();
+
+    for (porter, _, target, congestion) in movements.iter_mut() {
+        match admitted.get(porter) {
+            Some(true) => {
+                congestion.reserved = *target;
+                congestion.waiting = Duration::ZERO;
+            }
+            Some(false) => {
+                congestion.yielding = true;
+                congestion.waiting += delta;
+            }
+            None => {}
+        }
+    }
+}
+
+/// Decides which porters may step onto the tile they request, filling each tile up to its
+/// capacity with the porters that have waited the longest. Porters are returned in that order,
+/// each with whether it was let through.
+fn admit(
+    mut requests: Vec<(Entity, IVec2, Duration)>,
+    capacity: impl Fn(&IVec2) -> Option<u32>,
+    occupancy: impl Fn(&IVec2) -> u32,
+) -> Vec<(Entity, bool)> {
+    requests.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+
+    let mut admitted: HashMap<IVec2, u32> = HashMap::new();
+
+    requests
+        .into_iter()
+        .map(|(porter, target, _)| {
+            let admitted = admitted.entry(target).or_default();
+
+            let has_room =
+                capacity(&target).is_none_or(|capacity| occupancy(&target) + *admitted < capacity);
+
+            if has_room {
+                *admitted += 1;
+            }
+
+            (porter, has_room)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn porter(index: u32) -> Entity {
+        Entity::from_raw_u32(index).unwrap()
+    }
+
+    #[test]
+    fn test_longest_waiting_porter_gets_right_of_way() {
+        let tile = IVec2::new(3, 0);
+        let requests = vec![
+            (porter(1), tile, Duration::from_millis(200)),
+            (porter(2), tile, Duration::from_secs(2)),
+            (porter(3), tile, Duration::ZERO),
+        ];
+
+        let admitted = admit(requests, |_| Some(1), |_| 0);
+
+        assert_eq!(
+            admitted,
+            vec![(porter(2), true), (porter(1), false), (porter(3), false),]
+        );
+    }
+
+    #[test]
+    fn test_porters_queue_for_full_tiles() {
+        let full = IVec2::new(0, 1);
+        let roomy = IVec2::new(0, 2);
+        let open = IVec2::new(0, 3);
+        let requests = vec![
+            (porter(1), full, Duration::ZERO),
+            (porter(2), roomy, Duration::from_secs(1)),
+            (porter(3), roomy, Duration::ZERO),
+            (porter(4), open, Duration::ZERO),
+            (porter(5), open, Duration::ZERO),
+        ];
+
+        // Tiles without a path have no capacity, so anyone may walk onto them
+        let capacity = |coord: &IVec2| (*coord != open).then_some(2);
+        let occupancy = |coord: &IVec2| if *coord == full { 2 } else { 1 };
+
+        let admitted: HashMap<Entity, bool> =
+            admit(requests, capacity, occupancy).into_iter().collect();
+
+        assert!(!admitted[&porter(1)]);
+        assert!(admitted[&porter(2)]);
+        assert!(!admitted[&porter(3)]);
+        assert!(admitted[&porter(4)]);
+        assert!(admitted[&porter(5)]);
+    }
+
+    #[test]
+    fn test_reserved_tile_holds_over_ticks() {
+        let narrow = IVec2::new(1, 0);
+        let capacity = |coord: &IVec2| (*coord == narrow).then_some(1);
+
+        let mut first = Congestion::default();
+        let mut second = Congestion {
+            waiting: Duration::from_secs(1),
+            ..default()
+        };
+
+        let mut positions = [IVec2::new(0, 0), IVec2::new(2, 0)];
+        let mut targets = [narrow, narrow];
+
+        let tick = |positions: [IVec2; 2],
+                    targets: [IVec2; 2],
+                    first: &mut Congestion,
+                    second: &mut Congestion| {
+            let mut occupancy = HashMap::new();
+            for position in positions {
+                *occupancy.entry(position).or_default() += 1;
+            }
+
+            let mut movements = [
+                (porter(1), positions[0], Some(targets[0]), first),
+                (porter(2), positions[1], Some(targets[1]), second),
+            ];
+
+            regulate(
+                &mut movements,
+                capacity,
+                &occupancy,
+                Duration::from_millis(100),
+            );
+        };
+
+        // The second porter keeps right of way while crossing over, however long the first waits
+        for _ in 0..20 {
+            tick(positions, targets, &mut first, &mut second);
+
+            assert_eq!(second.reserved, Some(narrow));
+            assert!(first.yielding);
+            assert_eq!(first.reserved, None);
+        }
+
+        // Standing on the tile, it still fills it
+        positions[1] = narrow;
+        targets[1] = IVec2::new(1, 1);
+        tick(positions, targets, &mut first, &mut second);
+
+        assert_eq!(second.reserved, Some(targets[1]));
+        assert!(first.yielding);
+
+        // Once it moves on there is room again
+        positions[1] = IVec2::new(1, 1);
+        tick(positions, targets, &mut first, &mut second);
+
+        assert_eq!(first.reserved, Some(narrow));
+        assert!(!first.yielding);
+    }
+}
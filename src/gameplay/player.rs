@@ -1,25 +1,13 @@
-use std::time::Duration;
-
-use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy::prelude::*;
 use rand::Rng;
 
 use crate::{
-    gameplay::{
-        inventory::prelude::*,
-        people::{naming::NameManager, person},
-        random::Seed,
-    },
+    gameplay::{inventory::prelude::*, random::Seed},
     screens::Screen,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_player);
-
-    app.add_systems(
-        Update,
-        give_player_a_person
-            .run_if(in_state(Screen::Gameplay).and(on_timer(Duration::from_secs(5)))),
-    );
 }
 
 #[derive(Component, Reflect, Debug, Default)]
@@ -40,7 +28,3 @@ fn spawn_player(
         commands.spawn(item_stack_slot(player, item_handle, quantity));
     }
 }
-
-fn give_player_a_person(mut commands: Commands, mut name_manager: ResMut<NameManager>) {
-    commands.spawn(person(&mut name_manager));
-}
@@ -11,22 +11,27 @@ pub fn plugin(app: &mut App) {
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 #[relationship_target(relationship = ProgressBarFillOf, linked_spawn)]
-struct ProgressBarFill(Entity);
+pub struct ProgressBarFill(Entity);
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 #[relationship(relationship_target = ProgressBarFill)]
-struct ProgressBarFillOf(Entity);
+pub struct ProgressBarFillOf(pub Entity);
 
 fn on_proess_state_add(add: On<Add, ProcessState>, mut commands: Commands) {
-    commands.spawn((
+    commands.spawn(progress_bar(add.entity));
+}
+
+/// Bar floating above `owner`, filled by setting the width of its `ProgressBarFillOf` child
+pub fn progress_bar(owner: Entity) -> impl Bundle {
+    (
         Name::new("Progress Bar"),
-        ChildOf(add.entity),
+        ChildOf(owner),
         Transform::from_xyz(0.0, 48.0, 100.0),
         Sprite::from_color(Color::BLACK, Vec2::new(64.0, 16.0)),
         children![(
             Name::new("Progress Bar Fill"),
-            ProgressBarFillOf(add.entity),
+            ProgressBarFillOf(owner),
             Transform::from_xyz(-32.0, 0.0, 1.0),
             Sprite {
                 color: Color::linear_rgb(0.0, 0.8, 0.1),
@@ -35,7 +40,16 @@ fn on_proess_state_add(add: On<Add, ProcessState>, mut commands: Commands) {
             },
             Anchor::CENTER_LEFT,
         )],
-    ));
+    )
+}
+
+/// Fills a progress bar to the given fraction
+pub fn fill_progress_bar(sprite: &mut Sprite, progress: f32) {
+    let Some(rect) = sprite.rect else {
+        return;
+    };
+
+    sprite.custom_size = Some(Vec2::new(rect.width() * progress, rect.height()));
 }
 
 fn update_progress_bars(
@@ -47,16 +61,12 @@ fn update_progress_bars(
             continue;
         };
 
-        let Some(rect) = sprite.rect else {
-            continue;
-        };
-
         let progress = match state {
             ProcessState::InsufficientInput => 0.0,
             ProcessState::Working(timer) => timer.fraction(),
             ProcessState::Completed => 1.0,
         };
 
-        sprite.custom_size = Some(Vec2::new(rect.width() * progress, rect.height()));
+        fill_progress_bar(&mut sprite, progress);
     }
 }
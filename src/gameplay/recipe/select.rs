@@ -7,6 +7,7 @@ pub fn plugin(app: &mut App) {
     app.add_message::<RecipeChanged>();
 
     app.add_observer(on_select_recipe);
+    app.add_observer(on_clear_recipe);
 }
 
 #[derive(Component, Reflect, Default, Deref, DerefMut)]
@@ -20,6 +21,12 @@ pub struct SelectRecipe {
     pub recipe: AssetId<Recipe>,
 }
 
+/// Leaves the structure without a recipe, dropping the slots of the previous one
+#[derive(EntityEvent, Reflect)]
+pub struct ClearRecipe {
+    pub entity: Entity,
+}
+
 #[derive(Message, Reflect)]
 pub struct RecipeChanged(pub Entity);
 
@@ -66,3 +73,16 @@ fn on_select_recipe(
 
     recipe_changes.write(RecipeChanged(select_recipe.entity));
 }
+
+fn on_clear_recipe(
+    clear_recipe: On<ClearRecipe>,
+    mut commands: Commands,
+    mut recipe_changes: MessageWriter<RecipeChanged>,
+) {
+    commands
+        .entity(clear_recipe.entity)
+        .despawn_related::<Inventory>()
+        .remove::<SelectedRecipe>();
+
+    recipe_changes.write(RecipeChanged(clear_recipe.entity));
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::gameplay::{
+    FactorySystems,
+    inventory::prelude::*,
+    structure::{footprint::Footprint, orientation::Faces},
+    world::{
+        construction::Constructions,
+        tilemap::{CARDINALS, coord::Coord},
+    },
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(FixedUpdate, push_to_adjacent.in_set(FactorySystems::Work));
+}
+
+/// Pushes output directly into matching drop-off slots of structures sharing an edge, without
+/// the help of porters
+#[derive(Component, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct AdjacentTransfer(pub Timer);
+
+impl AdjacentTransfer {
+    pub fn per_second(rate: f32) -> Self {
+        Self(Timer::from_seconds(1.0 / rate, TimerMode::Repeating))
+    }
+}
+
+/// Pushes one item out of each output slot per tick, through the structure's output faces into a
+/// neighbour whose input face is turned towards it and whose matching drop-off slot has room
+fn push_to_adjacent(
+    structures: Query<(Entity, &Coord, &Footprint, &Faces, &mut AdjacentTransfer)>,
+    neighbours: Query<(&Coord, &Footprint, &Faces)>,
+    constructions: Res<Constructions>,
+    inventory: Query<&Inventory>,
+    pickup_stacks: Query<&ItemStack, With<Pickup>>,
+    stacks: Query<&ItemStack>,
+    drop_offs: Query<&DropOff>,
+    item_defs: Res<Assets<ItemDef>>,
+    time: Res<Time>,
+    mut transfer_items: MessageWriter<TransferItems>,
+) {
+    // Items already on their way into each slot this tick
+    let mut incoming: HashMap<Entity, u32> = HashMap::new();
+
+    for (structure, coord, footprint, faces, mut timer) in structures {
+        if !timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let receivers: Vec<Entity> = footprint
+            .tiles(coord.0)
+            .flat_map(|tile| {
+                CARDINALS
+                    .iter()
+                    .map(move |direction| (tile, tile + direction))
+            })
+            .filter(|(_, neighbour_tile)| {
+                faces.offers_output_to(coord.0, footprint, *neighbour_tile)
+            })
+            .filter_map(|(tile, neighbour_tile)| {
+                let neighbour = *constructions.get(&neighbour_tile)?;
+                let (neighbour_coord, neighbour_footprint, neighbour_faces) =
+                    neighbours.get(neighbour).ok()?;
+
+                (neighbour != structure
+                    && neighbour_faces.accepts_input_from(
+                        neighbour_coord.0,
+                        neighbour_footprint,
+                        tile,
+                    ))
+                .then_some(neighbour)
+            })
+            .collect();
+
+        for from_slot in inventory.iter_descendants(structure) {
+            let Ok(stack) = pickup_stacks.get(from_slot) else {
+                continue;
+            };
+
+            if stack.quantity == 0 {
+                continue;
+            }
+
+            let to_slot = receivers.iter().find_map(|receiver| {
+                inventory.iter_descendants(*receiver).find(|slot| {
+                    let has_room = slot_room(*slot, &stack.item, &stacks, &item_defs)
+                        > incoming.get(slot).copied().unwrap_or_default();
+
+                    has_room
+                        && drop_offs
+                            .get(*slot)
+                            .is_ok_and(|drop_off| drop_off.accepts(&stack.item, &item_defs))
+                })
+            });
+
+            if let Some(to_slot) = to_slot {
+                *incoming.entry(to_slot).or_default() += 1;
+
+                transfer_items.write(TransferItems {
+                    from_slot,
+                    to_slot,
+                    quantity: 1,
+                });
+            }
+        }
+    }
+}
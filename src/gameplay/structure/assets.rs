@@ -9,7 +9,14 @@ use crate::{
         loaders::toml::{FromToml, TomlAssetPlugin},
         tracking::LoadResource,
     },
-    gameplay::{inventory::prelude::ItemDef, recipe::assets::Recipe},
+    gameplay::{
+        inventory::prelude::ItemDef,
+        recipe::assets::Recipe,
+        structure::{
+            footprint::Footprint, junction::JunctionKind, orientation::Direction,
+            placement_rule::PlacementRule,
+        },
+    },
 };
 
 pub fn plugin(app: &mut App) {
@@ -43,26 +50,85 @@ impl FromWorld for StructureAssets {
 pub struct StructureRaw {
     pub id: String,
     pub name: String,
+    /// Sprite sheet of the structure, `sprites/structures/{id}.aseprite` if unset
+    pub sprite: Option<String>,
     pub default_recipe: Option<String>,
     #[serde(default)]
     pub cost: HashMap<String, u32>,
+    pub adjacent_transfer_rate: Option<f32>,
+    pub storage_slots: Option<u32>,
+    pub junction: Option<JunctionKind>,
+    #[serde(default)]
+    pub station: bool,
+    /// Tile offsets from the origin tile, which is always covered
+    #[serde(default)]
+    pub footprint: Vec<[i32; 2]>,
+    pub input_faces: Option<Vec<Direction>>,
+    pub output_faces: Option<Vec<Direction>>,
+    pub build_time: Option<f32>,
+    #[serde(default)]
+    pub placement_rules: Vec<PlacementRule>,
+    #[serde(default = "default_sight")]
+    pub sight: i32,
+}
+
+fn default_sight() -> i32 {
+    4
 }
 
 #[derive(Asset, Reflect, Debug)]
 pub struct StructureDef {
     pub id: String,
     pub name: String,
+    pub sprite: String,
     pub default_recipe: Option<AssetId<Recipe>>,
     pub cost: HashMap<Handle<ItemDef>, u32>,
+    /// Items per second pushed from output directly into neighbouring structures, if any. Rates
+    /// that aren't positive are dropped, disabling the transfer.
+    pub adjacent_transfer_rate: Option<f32>,
+    /// Number of general purpose slots, making the structure a storage
+    pub storage_slots: Option<u32>,
+    /// Junction behaviour, making the structure placeable on paths
+    pub junction: Option<JunctionKind>,
+    /// Whether carts can be scheduled to stop at the structure
+    pub station: bool,
+    /// Tiles covered by the structure
+    pub footprint: Footprint,
+    /// Sides accepting deliveries when facing north, all of them if unset
+    pub input_faces: Option<Vec<Direction>>,
+    /// Sides porters set off from when facing north, all of them if unset
+    pub output_faces: Option<Vec<Direction>>,
+    /// Seconds spent building once porters have delivered the cost, placing the structure as a
+    /// construction site. Structures without one are paid for up front and built instantly.
+    pub build_time: Option<f32>,
+    /// Conditions on the surroundings that must hold for the structure to be placed
+    pub placement_rules: Vec<PlacementRule>,
+    /// Radius around the structure revealed from the fog of war
+    pub sight: i32,
 }
 
 impl FromToml for StructureDef {
     type Raw = StructureRaw;
 
     fn from_toml(raw: Self::Raw, load_context: &mut bevy::asset::LoadContext) -> Self {
+        let adjacent_transfer_rate = raw.adjacent_transfer_rate.filter(|rate| {
+            let valid = rate.is_finite() && *rate > 0.0;
+
+            if !valid {
+                warn!("Ignoring adjacent transfer rate {rate} of {}", raw.id);
+            }
+
+            valid
+        });
+
+        let sprite = raw
+            .sprite
+            .unwrap_or_else(|| format!("sprites/structures/{}.aseprite", raw.id));
+
         Self {
             id: raw.id,
             name: raw.name,
+            sprite,
             default_recipe: raw.default_recipe.map(|recipe_id| {
                 load_context
                     .load(format!("manifests/recipes/{recipe_id}.recipe.toml"))
@@ -78,6 +144,24 @@ impl FromToml for StructureDef {
                     )
                 })
                 .collect(),
+            adjacent_transfer_rate,
+            storage_slots: raw.storage_slots,
+            junction: raw.junction,
+            station: raw.station,
+            footprint: {
+                let mut tiles: Vec<IVec2> = raw.footprint.into_iter().map(IVec2::from).collect();
+
+                if !tiles.contains(&IVec2::ZERO) {
+                    tiles.insert(0, IVec2::ZERO);
+                }
+
+                Footprint(tiles)
+            },
+            input_faces: raw.input_faces,
+            output_faces: raw.output_faces,
+            build_time: raw.build_time,
+            placement_rules: raw.placement_rules,
+            sight: raw.sight,
         }
     }
 }
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 
 use crate::gameplay::{
-    recipe::select::SelectRecipe,
+    recipe::select::{SelectRecipe, SelectedRecipe},
     structure::{Structure, assets::StructureDef, interactable::Interact},
     tome::inspect::Inspect,
     world::construction::StructureConstructed,
@@ -19,6 +19,7 @@ fn apply_default_recipe(
     mut commands: Commands,
     structure_query: Query<&Structure>,
     structure_defs: Res<Assets<StructureDef>>,
+    selected_recipes: Query<(), With<SelectedRecipe>>,
 ) {
     for StructureConstructed(entity) in structures_constructed.read() {
         let Ok(Structure(handle)) = structure_query.get(*entity) else {
@@ -29,13 +30,6 @@ fn apply_default_recipe(
             continue;
         };
 
-        if let Some(recipe) = structure_def.default_recipe {
-            commands.trigger(SelectRecipe {
-                entity: *entity,
-                recipe,
-            });
-        }
-
         commands
             .entity(*entity)
             .observe(|interact: On<Interact>, mut commands: Commands| {
@@ -43,5 +37,17 @@ fn apply_default_recipe(
                     entity: interact.entity,
                 });
             });
+
+        // Recipes chosen before construction, such as from a blueprint, are kept
+        if selected_recipes.contains(*entity) {
+            continue;
+        }
+
+        if let Some(recipe) = structure_def.default_recipe {
+            commands.trigger(SelectRecipe {
+                entity: *entity,
+                recipe,
+            });
+        }
     }
 }
@@ -1,11 +1,11 @@
 use std::collections::HashMap;
 
 use bevy::{asset::LoadedFolder, prelude::*, sprite::Anchor};
-use noise::{NoiseFn, OpenSimplex};
 use serde::Deserialize;
 
 use crate::{
     assets::{
+        indexing::{AssetIndexPlugin, IndexMap, Indexable},
         loaders::toml::{FromToml, TomlAssetPlugin},
         tracking::LoadResource,
     },
@@ -14,57 +14,175 @@ use crate::{
         sprite_sort::{YSortSprite, ZIndexSprite},
         world::{
             construction::Constructions,
+            fog::FogOfWar,
+            generation::{ChunkPlan, PlannedConstruct, WorldGen},
             tilemap::{
-                CHUNK_SIZE, TILE_SIZE,
-                chunk::{Chunk, ChunkLoaded, ChunkUnloaded},
+                TILE_SIZE,
+                chunk::{ChunkLoaded, ChunkUnloaded},
                 coord::Coord,
             },
         },
     },
-    screens::Screen,
 };
 
 pub fn plugin(app: &mut App) {
-    app.add_plugins(TomlAssetPlugin::<DepositDef>::extensions(&["deposit.toml"]));
+    app.add_plugins((
+        TomlAssetPlugin::<DepositDef>::extensions(&["deposit.toml"]),
+        AssetIndexPlugin::<DepositDef>::default(),
+    ));
     app.load_resource::<DepositAssets>();
 
-    app.init_resource::<DepositNoise>();
-    app.add_systems(OnEnter(Screen::Gameplay), create_noise);
-
     app.add_observer(spawn_deposits);
     app.add_observer(unload_deposits);
+
+    app.add_systems(FixedUpdate, regrow_deposits);
+    app.add_systems(Update, show_depletion);
 }
 
 #[derive(Deserialize)]
 pub struct DepositRaw {
     pub id: String,
     pub name: String,
+    /// Image the deposit is drawn with, `sprites/deposits/{id}.png` if unset
+    pub sprite: Option<String>,
     pub item_id: String,
     pub taxonomy: Taxonomy,
+    #[serde(flatten)]
+    pub generation: DepositGeneration,
+    /// Items regrown per second, making the deposit refill instead of vanishing once emptied
+    pub regeneration: Option<f32>,
+    /// Items a renewable deposit regrows up to, the quantity it spawned with if unset
+    pub max_quantity: Option<u32>,
+    #[serde(default = "default_depletion_stages")]
+    pub depletion_stages: u32,
+}
+
+fn default_depletion_stages() -> u32 {
+    1
+}
+
+/// How a deposit is scattered across the world
+#[derive(Reflect, Deserialize, Debug, Clone, PartialEq)]
+pub struct DepositGeneration {
+    /// Mixed with the world seed for the deposit's own noise
     pub seed: u32,
+    /// Weight the noise is multiplied by in each biome, by id. Deposits without any appear in
+    /// every biome, the others only in those listed.
+    #[serde(default)]
+    pub biomes: HashMap<String, f64>,
+    #[serde(default = "default_threshold")]
+    pub threshold: f64,
+    /// Scale applied to tile coordinates when sampling noise, larger values giving smaller clusters
+    #[serde(default = "default_noise_scale")]
+    pub noise_scale: f64,
+    #[serde(default)]
+    pub cluster: Cluster,
+    /// Items held by a deposit at the edge and at the core of its cluster
+    #[serde(default = "default_richness")]
+    pub richness: [u32; 2],
+    /// Whether the deposit only appears on tiles bordering a river
+    #[serde(default)]
+    pub riverbank: bool,
+}
+
+fn default_threshold() -> f64 {
+    0.4
+}
+
+fn default_noise_scale() -> f64 {
+    0.05
+}
+
+fn default_richness() -> [u32; 2] {
+    [100, 100]
+}
+
+/// How deposits fill the area where their noise exceeds the threshold
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum Cluster {
+    /// Every tile of the area
+    #[default]
+    Solid,
+    /// The given fraction of the area's tiles, picked at random
+    Sparse(f32),
+}
+
+impl DepositGeneration {
+    /// How far into its cluster the deposit is at a tile, from 0 at the edge to 1 at the core, if
+    /// the deposit appears there at all. `roll` is a random value between 0 and 1 for the tile,
+    /// deciding which tiles sparse clusters keep.
+    pub fn strength(&self, noise_value: f64, biome: Option<&str>, roll: f32) -> Option<f64> {
+        let affinity = if self.biomes.is_empty() {
+            1.0
+        } else {
+            biome
+                .and_then(|biome| self.biomes.get(biome))
+                .copied()
+                .unwrap_or(0.0)
+        };
+
+        let value = noise_value * affinity;
+        if value <= self.threshold {
+            return None;
+        }
+
+        if let Cluster::Sparse(density) = self.cluster
+            && roll >= density
+        {
+            return None;
+        }
+
+        Some(((value - self.threshold) / (1.0 - self.threshold)).clamp(0.0, 1.0))
+    }
+
+    /// Items held by a deposit of the given strength
+    pub fn quantity(&self, strength: f64) -> u32 {
+        let [edge, core] = self.richness;
+
+        (edge as f64 + (core as f64 - edge as f64) * strength).round() as u32
+    }
 }
 
 #[derive(Asset, Reflect, Debug)]
 pub struct DepositDef {
     pub id: String,
     pub name: String,
+    pub sprite: String,
     pub item_id: AssetId<ItemDef>,
     pub taxonomy: Taxonomy,
-    pub seed: u32,
+    pub generation: DepositGeneration,
+    pub regeneration: Option<f32>,
+    pub max_quantity: Option<u32>,
+    /// Steps the sprite shrinks through as the deposit empties
+    pub depletion_stages: u32,
+}
+
+impl Indexable for DepositDef {
+    fn index(&self) -> &String {
+        &self.id
+    }
 }
 
 impl FromToml for DepositDef {
     type Raw = DepositRaw;
 
     fn from_toml(raw: Self::Raw, load_context: &mut bevy::asset::LoadContext) -> Self {
+        let sprite = raw
+            .sprite
+            .unwrap_or_else(|| format!("sprites/deposits/{}.png", raw.id));
+
         Self {
             id: raw.id,
             name: raw.name,
+            sprite,
             item_id: load_context
                 .load(format!("manifests/items/{}.item.toml", raw.item_id))
                 .id(),
             taxonomy: raw.taxonomy,
-            seed: raw.seed,
+            generation: raw.generation,
+            regeneration: raw.regeneration,
+            max_quantity: raw.max_quantity,
+            depletion_stages: raw.depletion_stages,
         }
     }
 }
@@ -73,6 +191,30 @@ impl FromToml for DepositDef {
 #[reflect(Component)]
 pub struct Deposit(pub Handle<DepositDef>);
 
+/// Quantity a deposit counts as full at, for showing how depleted it is
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct DepositCapacity(pub u32);
+
+/// Refills a renewable deposit over time
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Regrowth {
+    pub per_second: f32,
+    pub max: u32,
+    /// Fraction of an item regrown so far
+    pub progress: f32,
+}
+
+/// Depletion stage of a deposit, from 0 once empty up to `stages` while full
+pub fn depletion_stage(quantity: u32, capacity: u32, stages: u32) -> u32 {
+    if capacity == 0 {
+        return 0;
+    }
+
+    (quantity.min(capacity) * stages).div_ceil(capacity)
+}
+
 #[derive(Asset, Resource, Reflect, Clone)]
 #[reflect(Resource)]
 pub struct DepositAssets {
@@ -91,97 +233,209 @@ impl FromWorld for DepositAssets {
     }
 }
 
-#[derive(Resource, Debug, Default)]
-pub struct DepositNoise {
-    pub noises: HashMap<AssetId<DepositDef>, OpenSimplex>,
-}
-
-fn create_noise(mut deposit_noise: ResMut<DepositNoise>, deposit_defs: Res<Assets<DepositDef>>) {
-    for (deposit_id, deposit_def) in deposit_defs.iter() {
-        let fbm = OpenSimplex::new(deposit_def.seed);
-        deposit_noise.noises.insert(deposit_id, fbm);
-    }
-}
-
+/// Spawns the deposits generation planned for a chunk
 fn spawn_deposits(
     chunk_loaded: On<ChunkLoaded>,
-    chunk_query: Query<&Chunk>,
+    plans: Query<&ChunkPlan>,
     mut commands: Commands,
+    world_gen: Res<WorldGen>,
+    deposit_index: Res<IndexMap<DepositDef>>,
     deposit_defs: Res<Assets<DepositDef>>,
     asset_server: Res<AssetServer>,
-    deposit_noise: Res<DepositNoise>,
+    fog: Res<FogOfWar>,
     mut constructions: ResMut<Constructions>,
 ) {
-    let chunk = chunk_query.get(chunk_loaded.chunk).unwrap();
+    let Ok(plan) = plans.get(chunk_loaded.chunk) else {
+        return;
+    };
+
+    for (_, coord, tile) in plan.iter() {
+        let Some(PlannedConstruct::Deposit { deposit, quantity }) = tile.construct else {
+            continue;
+        };
+
+        if constructions.contains_key(&coord) {
+            continue;
+        }
+
+        let Some(deposit_id) = world_gen
+            .spec
+            .deposits
+            .get(deposit)
+            .and_then(|spec| deposit_index.get(&spec.id))
+        else {
+            continue;
+        };
+
+        let Some(deposit_def) = deposit_defs.get(*deposit_id) else {
+            continue;
+        };
+
+        let visibility = if fog.is_revealed(coord) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        let entity = commands
+            .spawn((
+                Name::new(deposit_def.name.clone()),
+                visibility,
+                Deposit(asset_server.get_id_handle(*deposit_id).unwrap()),
+                Coord(coord),
+                Anchor(Vec2::new(0.0, -0.25)),
+                YSortSprite,
+                ZIndexSprite(10),
+                Sprite {
+                    image: asset_server.load(&deposit_def.sprite),
+                    custom_size: Vec2::new(TILE_SIZE.x, TILE_SIZE.y).into(),
+                    ..default()
+                },
+            ))
+            .id();
+
+        commands.spawn(item_stack_slot(
+            entity,
+            asset_server.get_id_handle(deposit_def.item_id).unwrap(),
+            quantity,
+        ));
+
+        if let Some(per_second) = deposit_def.regeneration {
+            commands.entity(entity).insert(Regrowth {
+                per_second,
+                max: deposit_def.max_quantity.unwrap_or(quantity),
+                progress: 0.0,
+            });
+        }
+
+        commands.entity(entity).insert(DepositCapacity(
+            deposit_def.max_quantity.unwrap_or(quantity),
+        ));
 
-    let absolute_chunk_position = chunk.0 * CHUNK_SIZE.as_ivec2();
+        constructions.insert(coord, entity);
+    }
+}
+
+fn regrow_deposits(
+    deposits: Query<(Entity, &mut Regrowth)>,
+    inventory: Query<&Inventory>,
+    mut stacks: Query<&mut ItemStack>,
+    time: Res<Time>,
+) {
+    for (deposit, mut regrowth) in deposits {
+        let Some(slot) = inventory
+            .iter_descendants(deposit)
+            .find(|slot| stacks.contains(*slot))
+        else {
+            continue;
+        };
 
-    for (deposit_id, deposit_def) in deposit_defs.iter() {
-        let Some(noise) = deposit_noise.noises.get(&deposit_id) else {
-            return;
+        let Ok(mut stack) = stacks.get_mut(slot) else {
+            continue;
         };
 
-        for x in 0..CHUNK_SIZE.x {
-            for y in 0..CHUNK_SIZE.y {
-                let absolute_tile_pos = absolute_chunk_position + ivec2(x as i32, y as i32);
-                if constructions.contains_key(&absolute_tile_pos) {
-                    continue;
-                }
-
-                let value = noise.get((absolute_tile_pos.as_dvec2() * 0.05).into());
-                if value <= 0.4 {
-                    continue;
-                }
-
-                let entity = commands
-                    .spawn((
-                        Name::new(deposit_def.name.clone()),
-                        Deposit(asset_server.get_id_handle(deposit_id).unwrap()),
-                        Coord(absolute_tile_pos),
-                        Anchor(Vec2::new(0.0, -0.25)),
-                        YSortSprite,
-                        ZIndexSprite(10),
-                        Sprite {
-                            image: asset_server
-                                .load(format!("sprites/deposits/{}.png", deposit_def.id)),
-                            custom_size: Vec2::new(TILE_SIZE.x, TILE_SIZE.y).into(),
-                            ..default()
-                        },
-                    ))
-                    .id();
-
-                commands.spawn(item_stack_slot(
-                    entity,
-                    asset_server.get_id_handle(deposit_def.item_id).unwrap(),
-                    100,
-                ));
-
-                constructions.insert(absolute_tile_pos, entity);
-            }
+        if stack.quantity >= regrowth.max {
+            regrowth.progress = 0.0;
+            continue;
         }
+
+        regrowth.progress += regrowth.per_second * time.delta_secs();
+
+        let regrown = regrowth.progress.floor();
+
+        // Stacks are only written once a whole item has grown back, so they aren't marked changed
+        // every tick
+        if regrown >= 1.0 {
+            regrowth.progress -= regrown;
+            stack.quantity = (stack.quantity + regrown as u32).min(regrowth.max);
+        }
+    }
+}
+
+/// Shrinks deposit sprites stage by stage as they empty
+fn show_depletion(
+    stacks: Query<(&ItemStack, &InInventory), Changed<ItemStack>>,
+    mut deposits: Query<(&Deposit, &DepositCapacity, &mut Sprite)>,
+    deposit_defs: Res<Assets<DepositDef>>,
+) {
+    for (stack, in_inventory) in stacks {
+        let Ok((deposit, capacity, mut sprite)) = deposits.get_mut(in_inventory.0) else {
+            continue;
+        };
+
+        let Some(deposit_def) = deposit_defs.get(&deposit.0) else {
+            continue;
+        };
+
+        let stages = deposit_def.depletion_stages.max(1);
+        let stage = depletion_stage(stack.quantity, capacity.0, stages);
+        let scale = 0.4 + 0.6 * stage as f32 / stages as f32;
+
+        sprite.custom_size = Some(TILE_SIZE * scale);
     }
 }
 
 fn unload_deposits(
     chunk_unloaded: On<ChunkUnloaded>,
-    chunk_query: Query<&Chunk>,
+    plans: Query<&ChunkPlan>,
     mut constructions: ResMut<Constructions>,
     mut commands: Commands,
 ) {
-    let chunk = chunk_query.get(chunk_unloaded.chunk).unwrap();
+    let Ok(plan) = plans.get(chunk_unloaded.chunk) else {
+        return;
+    };
 
-    let absolute_chunk_position = chunk.0 * CHUNK_SIZE.as_ivec2();
+    for (_, coord, _) in plan.iter() {
+        let Some(construction) = constructions.get(&coord) else {
+            continue;
+        };
 
-    for x in 0..CHUNK_SIZE.x {
-        for y in 0..CHUNK_SIZE.y {
-            let absolute_tile_pos = absolute_chunk_position + ivec2(x as i32, y as i32);
+        commands.entity(*construction).despawn();
+        constructions.remove(&coord);
+    }
+}
 
-            let Some(construction) = constructions.get(&absolute_tile_pos) else {
-                continue;
-            };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            commands.entity(*construction).despawn();
-            constructions.remove(&absolute_tile_pos);
+    fn generation(biomes: &[(&str, f64)], cluster: Cluster) -> DepositGeneration {
+        DepositGeneration {
+            seed: 0,
+            biomes: biomes
+                .iter()
+                .map(|(biome, affinity)| (biome.to_string(), *affinity))
+                .collect(),
+            threshold: 0.5,
+            noise_scale: 0.05,
+            cluster,
+            richness: [50, 150],
+            riverbank: false,
         }
     }
+
+    #[test]
+    fn test_deposit_strength_follows_noise_and_biome() {
+        let anywhere = generation(&[], Cluster::Solid);
+        assert_eq!(anywhere.strength(0.4, None, 0.0), None);
+        assert_eq!(anywhere.strength(0.75, None, 0.0), Some(0.5));
+        assert_eq!(anywhere.quantity(0.0), 50);
+        assert_eq!(anywhere.quantity(0.5), 100);
+        assert_eq!(anywhere.quantity(1.0), 150);
+
+        let swampy = generation(&[("swamp", 2.0)], Cluster::Sparse(0.5));
+        assert_eq!(swampy.strength(0.75, Some("swamp"), 0.2), Some(1.0));
+        assert_eq!(swampy.strength(0.75, Some("swamp"), 0.7), None);
+        assert_eq!(swampy.strength(0.75, Some("desert"), 0.2), None);
+    }
+
+    #[test]
+    fn test_depletion_stage_rounds_up() {
+        assert_eq!(depletion_stage(100, 100, 3), 3);
+        assert_eq!(depletion_stage(67, 100, 3), 3);
+        assert_eq!(depletion_stage(66, 100, 3), 2);
+        assert_eq!(depletion_stage(1, 100, 3), 1);
+        assert_eq!(depletion_stage(0, 100, 3), 0);
+        assert_eq!(depletion_stage(120, 100, 3), 3);
+    }
 }
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use crate::gameplay::world::tilemap::{
+    CARDINALS,
+    coord::{Coord, coord_to_translation},
+};
+
+/// Tiles covered by a construct, as offsets from the tile at its `Coord`
+#[derive(Component, Reflect, Debug, Clone, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct Footprint(pub Vec<IVec2>);
+
+impl Default for Footprint {
+    fn default() -> Self {
+        Self(vec![IVec2::ZERO])
+    }
+}
+
+impl Footprint {
+    /// Absolute tiles covered when the footprint's origin sits at `origin`
+    pub fn tiles(&self, origin: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        self.0.iter().map(move |offset| origin + offset)
+    }
+
+    /// Tiles sharing an edge with the footprint at `origin` without being covered by it
+    pub fn neighbours(&self, origin: IVec2) -> Vec<IVec2> {
+        let mut neighbours = vec![];
+
+        for tile in self.tiles(origin) {
+            for neighbour in CARDINALS.iter().map(|c| tile + c) {
+                if !self.0.contains(&(neighbour - origin)) && !neighbours.contains(&neighbour) {
+                    neighbours.push(neighbour);
+                }
+            }
+        }
+
+        neighbours
+    }
+
+    /// Offset from the origin tile's translation to the middle of the footprint, where the
+    /// sprite is anchored
+    pub fn centre(&self) -> Vec2 {
+        let sum: Vec2 = self
+            .0
+            .iter()
+            .map(|offset| coord_to_translation(&Coord(*offset)))
+            .sum();
+
+        sum / self.0.len().max(1) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_tile_neighbours_are_cardinals() {
+        let origin = IVec2::new(2, -1);
+        let neighbours = Footprint::default().neighbours(origin);
+
+        assert_eq!(
+            neighbours,
+            CARDINALS.iter().map(|c| origin + c).collect::<Vec<_>>()
+        );
+        assert_eq!(Footprint::default().centre(), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_square_footprint_neighbours_surround_it() {
+        let footprint = Footprint(vec![
+            IVec2::new(0, 0),
+            IVec2::new(1, 0),
+            IVec2::new(0, 1),
+            IVec2::new(1, 1),
+        ]);
+
+        let neighbours = footprint.neighbours(IVec2::ZERO);
+
+        assert_eq!(neighbours.len(), 8);
+        assert!(
+            neighbours
+                .iter()
+                .all(|n| !footprint.tiles(IVec2::ZERO).any(|t| t == *n))
+        );
+        assert!(neighbours.contains(&IVec2::new(2, 1)));
+        assert!(neighbours.contains(&IVec2::new(-1, 0)));
+    }
+}
@@ -23,7 +23,7 @@ impl Default for HighlightColor {
     }
 }
 
-fn highlight_pickable(query: Query<(&mut Sprite, &PickingInteraction)>) {
+pub fn highlight_pickable(query: Query<(&mut Sprite, &PickingInteraction)>) {
     for (mut sprite, picking_interaction) in query {
         if *picking_interaction != PickingInteraction::None {
             sprite.color = HIGHLIGHT_COLOR;
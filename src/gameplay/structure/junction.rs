@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::gameplay::{
+    inventory::prelude::*,
+    people::porting::Walkable,
+    structure::{Structure, assets::StructureDef},
+    world::{construction::StructureConstructed, tilemap::CARDINALS},
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        attach_junction.run_if(on_message::<StructureConstructed>),
+    );
+}
+
+/// Kinds of junctions a structure manifest can declare
+#[derive(Reflect, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JunctionKind {
+    Sorter,
+    Splitter,
+}
+
+/// Logistic structure placed on a path, deciding which branch porters take when passing through
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Walkable)]
+pub enum Junction {
+    /// Sends items passing the filter down the branch in the given direction, and all other items
+    /// down the remaining branches
+    Sorter {
+        filter: Option<ItemFilter>,
+        branch: IVec2,
+    },
+    /// Alternates between branches
+    Splitter { next: usize },
+}
+
+impl From<JunctionKind> for Junction {
+    fn from(kind: JunctionKind) -> Self {
+        match kind {
+            JunctionKind::Sorter => Junction::Sorter {
+                filter: None,
+                branch: CARDINALS[0],
+            },
+            JunctionKind::Splitter => Junction::Splitter { next: 0 },
+        }
+    }
+}
+
+impl Junction {
+    /// Narrows down the branches a porter carrying `item` may take, out of the directions it could
+    /// walk in. The porter picks among the returned branches as it would at any intersection.
+    pub fn route(
+        &mut self,
+        item: &Handle<ItemDef>,
+        item_defs: &Assets<ItemDef>,
+        branches: &[IVec2],
+    ) -> Vec<IVec2> {
+        match self {
+            Junction::Sorter {
+                filter: Some(filter),
+                branch,
+            } => {
+                let sorted: Vec<IVec2> = if filter.matches(item, item_defs) {
+                    branches.iter().copied().filter(|b| b == branch).collect()
+                } else {
+                    branches.iter().copied().filter(|b| b != branch).collect()
+                };
+
+                if sorted.is_empty() {
+                    branches.to_vec()
+                } else {
+                    sorted
+                }
+            }
+            Junction::Sorter { filter: None, .. } => branches.to_vec(),
+            Junction::Splitter { next } => {
+                let ordered: Vec<IVec2> = CARDINALS
+                    .iter()
+                    .copied()
+                    .filter(|dir| branches.contains(dir))
+                    .collect();
+
+                if ordered.is_empty() {
+                    return Vec::new();
+                }
+
+                let index = *next % ordered.len();
+                *next = next.wrapping_add(1);
+
+                vec![ordered[index]]
+            }
+        }
+    }
+}
+
+fn attach_junction(
+    mut structures_constructed: MessageReader<StructureConstructed>,
+    structures: Query<&Structure>,
+    structure_defs: Res<Assets<StructureDef>>,
+    mut commands: Commands,
+) {
+    for StructureConstructed(entity) in structures_constructed.read() {
+        let Some(kind) = structures
+            .get(*entity)
+            .ok()
+            .and_then(|structure| structure_defs.get(&structure.0))
+            .and_then(|structure_def| structure_def.junction)
+        else {
+            continue;
+        };
+
+        commands.entity(*entity).insert(Junction::from(kind));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gameplay::world::tilemap::{EAST, NORTH, SOUTH, WEST};
+
+    use super::*;
+
+    #[test]
+    fn test_splitter_alternates_branches() {
+        let mut splitter = Junction::Splitter { next: 0 };
+        let item = Handle::default();
+        let item_defs = Assets::default();
+
+        let branches = [EAST, NORTH];
+
+        let picks: Vec<Vec<IVec2>> = (0..4)
+            .map(|_| splitter.route(&item, &item_defs, &branches))
+            .collect();
+
+        assert_eq!(
+            picks,
+            vec![vec![NORTH], vec![EAST], vec![NORTH], vec![EAST]]
+        );
+    }
+
+    #[test]
+    fn test_sorter_sends_matching_items_down_branch() {
+        let item: Handle<ItemDef> = Handle::default();
+        let item_defs = Assets::default();
+
+        let mut sorter = Junction::Sorter {
+            filter: Some(ItemFilter::Item(item.clone())),
+            branch: WEST,
+        };
+
+        assert_eq!(
+            sorter.route(&item, &item_defs, &[NORTH, WEST, SOUTH]),
+            vec![WEST]
+        );
+    }
+
+    #[test]
+    fn test_sorter_keeps_other_items_off_branch() {
+        let item_defs = Assets::default();
+
+        let mut sorter = Junction::Sorter {
+            filter: Some(ItemFilter::Tag(ItemTag::Rotting)),
+            branch: WEST,
+        };
+
+        assert_eq!(
+            sorter.route(&Handle::default(), &item_defs, &[NORTH, WEST, SOUTH]),
+            vec![NORTH, SOUTH]
+        );
+    }
+}
@@ -3,28 +3,49 @@ use std::time::Duration;
 use bevy::prelude::*;
 
 use crate::gameplay::{
-    people::porting::PorterCooldown, structure::assets::StructureDef,
+    people::porting::PorterCooldown,
+    structure::{
+        assets::StructureDef,
+        footprint::Footprint,
+        orientation::{Faces, Orientation},
+    },
     world::demolition::Demolishable,
 };
 
+pub mod adjacent_transfer;
 pub mod assets;
 pub mod default_recipe;
 pub mod deposit;
+pub mod footprint;
 pub mod foragers_outpost;
 pub mod highlight;
 pub mod interactable;
+pub mod junction;
+pub mod obstacle;
+pub mod orientation;
 pub mod path;
+pub mod placement_rule;
 pub mod range;
+pub mod station;
+pub mod storage;
+pub mod town;
 
 pub fn plugin(app: &mut App) {
     app.add_plugins((
+        adjacent_transfer::plugin,
         assets::plugin,
         default_recipe::plugin,
         deposit::plugin,
         foragers_outpost::plugin,
         highlight::plugin,
         interactable::plugin,
+        junction::plugin,
+        obstacle::plugin,
+        orientation::plugin,
         path::plugin,
+        station::plugin,
+        storage::plugin,
+        town::plugin,
     ));
 }
 
@@ -32,6 +53,9 @@ pub fn plugin(app: &mut App) {
 #[reflect(Component)]
 #[require(
     PorterCooldown(Timer::new(Duration::from_secs(1), TimerMode::Once)),
-    Demolishable
+    Demolishable,
+    Footprint,
+    Orientation,
+    Faces
 )]
 pub struct Structure(pub Handle<StructureDef>);
@@ -0,0 +1,266 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{asset::LoadedFolder, prelude::*, sprite::Anchor};
+use serde::Deserialize;
+
+use crate::{
+    assets::{
+        indexing::{AssetIndexPlugin, IndexMap, Indexable},
+        loaders::toml::{FromToml, TomlAssetPlugin},
+        tracking::LoadResource,
+    },
+    gameplay::{
+        FactorySystems,
+        hud::hotbar::HotbarSelection,
+        inventory::prelude::*,
+        player::Player,
+        sprite_sort::{YSortSprite, ZIndexSprite},
+        world::{
+            construction::Constructions,
+            fog::FogOfWar,
+            generation::{ChunkPlan, PlannedConstruct, WorldGen},
+            tilemap::{TILE_SIZE, chunk::ChunkLoaded, coord::Coord},
+        },
+    },
+    screens::Screen,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins((
+        TomlAssetPlugin::<ObstacleDef>::extensions(&["obstacle.toml"]),
+        AssetIndexPlugin::<ObstacleDef>::default(),
+    ));
+    app.load_resource::<ObstacleAssets>();
+
+    app.init_resource::<ClearedObstacles>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_cleared_obstacles);
+
+    app.add_observer(spawn_obstacles);
+    app.add_observer(start_clearing);
+
+    app.add_systems(
+        FixedUpdate,
+        clear_obstacles.in_set(FactorySystems::Demolish),
+    );
+}
+
+#[derive(Deserialize)]
+pub struct ObstacleRaw {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    /// Fraction of tiles covered by the obstacle in each biome, by id
+    pub biomes: HashMap<String, f32>,
+    pub clear_time: f32,
+    #[serde(default)]
+    pub yields: HashMap<String, u32>,
+}
+
+#[derive(Asset, Reflect, Debug)]
+pub struct ObstacleDef {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    pub biomes: HashMap<String, f32>,
+    /// Seconds it takes to clear the obstacle
+    pub clear_time: f32,
+    /// Items given to the player once the obstacle is cleared
+    pub yields: HashMap<Handle<ItemDef>, u32>,
+}
+
+impl Indexable for ObstacleDef {
+    fn index(&self) -> &String {
+        &self.id
+    }
+}
+
+impl FromToml for ObstacleDef {
+    type Raw = ObstacleRaw;
+
+    fn from_toml(raw: Self::Raw, load_context: &mut bevy::asset::LoadContext) -> Self {
+        Self {
+            id: raw.id,
+            name: raw.name,
+            sprite: raw.sprite,
+            biomes: raw.biomes,
+            clear_time: raw.clear_time,
+            yields: raw
+                .yields
+                .iter()
+                .map(|(key, val)| {
+                    (
+                        load_context.load(format!("manifests/items/{key}.item.toml")),
+                        *val,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Asset, Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct ObstacleAssets {
+    manifest_folder: Handle<LoadedFolder>,
+}
+
+impl FromWorld for ObstacleAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+
+        Self {
+            manifest_folder: assets.load_folder("manifests/obstacles"),
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Pickable)]
+pub struct Obstacle(pub Handle<ObstacleDef>);
+
+/// Obstacle being cleared, removed once the timer finishes
+#[derive(Component, Reflect, Debug, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct Clearing(pub Timer);
+
+/// Coordinates of every obstacle cleared so far, kept so they aren't spawned again when their
+/// chunk loads anew
+#[derive(Resource, Reflect, Debug, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct ClearedObstacles(pub HashSet<IVec2>);
+
+fn reset_cleared_obstacles(mut cleared_obstacles: ResMut<ClearedObstacles>) {
+    *cleared_obstacles = ClearedObstacles::default();
+}
+
+/// Spawns the obstacles generation planned for a chunk, save those already cleared
+fn spawn_obstacles(
+    chunk_loaded: On<ChunkLoaded>,
+    plans: Query<&ChunkPlan>,
+    mut commands: Commands,
+    world_gen: Res<WorldGen>,
+    obstacle_index: Res<IndexMap<ObstacleDef>>,
+    obstacle_defs: Res<Assets<ObstacleDef>>,
+    asset_server: Res<AssetServer>,
+    fog: Res<FogOfWar>,
+    cleared_obstacles: Res<ClearedObstacles>,
+    mut constructions: ResMut<Constructions>,
+) {
+    let Ok(plan) = plans.get(chunk_loaded.chunk) else {
+        return;
+    };
+
+    for (_, coord, tile) in plan.iter() {
+        let Some(PlannedConstruct::Obstacle(obstacle)) = tile.construct else {
+            continue;
+        };
+
+        if constructions.contains_key(&coord) || cleared_obstacles.contains(&coord) {
+            continue;
+        }
+
+        let Some(obstacle_id) = world_gen
+            .spec
+            .obstacles
+            .get(obstacle)
+            .and_then(|spec| obstacle_index.get(&spec.id))
+        else {
+            continue;
+        };
+
+        let Some(obstacle_def) = obstacle_defs.get(*obstacle_id) else {
+            continue;
+        };
+
+        let visibility = if fog.is_revealed(coord) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        let entity = commands
+            .spawn((
+                Name::new(obstacle_def.name.clone()),
+                visibility,
+                Obstacle(asset_server.get_id_handle(*obstacle_id).unwrap()),
+                Coord(coord),
+                Anchor(Vec2::new(0.0, -0.25)),
+                YSortSprite,
+                ZIndexSprite(10),
+                Sprite {
+                    image: asset_server.load(&obstacle_def.sprite),
+                    custom_size: Vec2::new(TILE_SIZE.x, TILE_SIZE.y).into(),
+                    ..default()
+                },
+            ))
+            .id();
+
+        constructions.insert(coord, entity);
+    }
+}
+
+/// Starts clearing an obstacle clicked on while nothing is selected for placement
+fn start_clearing(
+    click: On<Pointer<Click>>,
+    obstacles: Query<(&Obstacle, &Coord), Without<Clearing>>,
+    obstacle_defs: Res<Assets<ObstacleDef>>,
+    fog: Res<FogOfWar>,
+    hotbar_selection: HotbarSelection,
+    mut commands: Commands,
+) {
+    if click.button != PointerButton::Primary || hotbar_selection.action().is_some() {
+        return;
+    }
+
+    let Ok((obstacle, coord)) = obstacles.get(click.entity) else {
+        return;
+    };
+
+    // Obstacles hidden by the fog of war can't be reached yet
+    if !fog.is_revealed(coord.0) {
+        return;
+    }
+
+    let Some(obstacle_def) = obstacle_defs.get(&obstacle.0) else {
+        return;
+    };
+
+    commands
+        .entity(click.entity)
+        .insert(Clearing(Timer::from_seconds(
+            obstacle_def.clear_time,
+            TimerMode::Once,
+        )));
+}
+
+fn clear_obstacles(
+    obstacles: Query<(Entity, &Obstacle, &Coord, &mut Clearing, &mut Sprite)>,
+    obstacle_defs: Res<Assets<ObstacleDef>>,
+    mut constructions: ResMut<Constructions>,
+    mut cleared_obstacles: ResMut<ClearedObstacles>,
+    player: Single<Entity, With<Player>>,
+    inventory: Query<&Inventory>,
+    mut stacks: Query<&mut ItemStack>,
+    mut commands: Commands,
+    time: Res<Time>,
+) {
+    for (entity, obstacle, coord, mut clearing, mut sprite) in obstacles {
+        clearing.tick(time.delta());
+
+        sprite.color = Color::WHITE.with_alpha(1.0 - clearing.fraction() * 0.75);
+
+        if !clearing.is_finished() {
+            continue;
+        }
+
+        if let Some(obstacle_def) = obstacle_defs.get(&obstacle.0) {
+            refund(*player, &obstacle_def.yields, &inventory, &mut stacks);
+        }
+
+        constructions.remove(&coord.0);
+        cleared_obstacles.insert(coord.0);
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,220 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gameplay::{
+        hud::hotbar::{HotbarActionKind, HotbarSelection},
+        structure::{assets::StructureDef, footprint::Footprint},
+        world::{
+            construction::ConstructionPreview,
+            tilemap::{EAST, NORTH, SOUTH, WEST},
+        },
+    },
+    input::input_map::{Action, action_just_pressed},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<PlacementOrientation>();
+
+    app.add_systems(
+        Update,
+        (
+            rotate_placement.run_if(action_just_pressed(Action::Rotate)),
+            orient_preview,
+        )
+            .chain(),
+    );
+}
+
+/// Compass direction on the tile grid, relative to a structure facing north in manifests
+#[derive(Reflect, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn clockwise(self) -> Self {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn as_ivec2(self) -> IVec2 {
+        match self {
+            Direction::North => NORTH,
+            Direction::East => EAST,
+            Direction::South => SOUTH,
+            Direction::West => WEST,
+        }
+    }
+}
+
+/// Direction a structure faces, rotating its footprint and faces from how the manifest lists them
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Component)]
+pub struct Orientation(pub Direction);
+
+impl Orientation {
+    /// Rotates an offset or direction given for a structure facing north
+    pub fn apply(&self, offset: IVec2) -> IVec2 {
+        match self.0 {
+            Direction::North => offset,
+            Direction::East => IVec2::new(offset.y, -offset.x),
+            Direction::South => -offset,
+            Direction::West => IVec2::new(-offset.y, offset.x),
+        }
+    }
+
+    pub fn footprint(&self, footprint: &Footprint) -> Footprint {
+        Footprint(
+            footprint
+                .0
+                .iter()
+                .map(|offset| self.apply(*offset))
+                .collect(),
+        )
+    }
+
+    /// Sprites only come drawn facing one way, so sideways structures are mirrored instead
+    pub fn flips_sprite(&self) -> bool {
+        matches!(self.0, Direction::East | Direction::West)
+    }
+}
+
+/// Sides of a structure porters may deliver inputs to and collect outputs from. `None` leaves every
+/// side open.
+#[derive(Component, Reflect, Debug, Default, Clone)]
+#[reflect(Component)]
+pub struct Faces {
+    pub inputs: Option<Vec<IVec2>>,
+    pub outputs: Option<Vec<IVec2>>,
+}
+
+impl Faces {
+    pub fn new(structure_def: &StructureDef, orientation: &Orientation) -> Self {
+        let rotate = |faces: &Option<Vec<Direction>>| {
+            faces.as_ref().map(|faces| {
+                faces
+                    .iter()
+                    .map(|face| orientation.apply(face.as_ivec2()))
+                    .collect()
+            })
+        };
+
+        Self {
+            inputs: rotate(&structure_def.input_faces),
+            outputs: rotate(&structure_def.output_faces),
+        }
+    }
+
+    /// Whether a porter standing on `tile` may deliver to the structure
+    pub fn accepts_input_from(&self, origin: IVec2, footprint: &Footprint, tile: IVec2) -> bool {
+        on_face(&self.inputs, origin, footprint, tile)
+    }
+
+    /// Whether a porter may set off from the structure onto `tile`
+    pub fn offers_output_to(&self, origin: IVec2, footprint: &Footprint, tile: IVec2) -> bool {
+        on_face(&self.outputs, origin, footprint, tile)
+    }
+}
+
+fn on_face(faces: &Option<Vec<IVec2>>, origin: IVec2, footprint: &Footprint, tile: IVec2) -> bool {
+    let Some(faces) = faces else {
+        return true;
+    };
+
+    footprint
+        .tiles(origin)
+        .any(|covered| faces.contains(&(tile - covered)))
+}
+
+/// Orientation structures are placed with
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct PlacementOrientation(pub Orientation);
+
+fn rotate_placement(mut placement_orientation: ResMut<PlacementOrientation>) {
+    let direction = placement_orientation.0.0.clockwise();
+    placement_orientation.0 = Orientation(direction);
+}
+
+fn orient_preview(
+    placement_orientation: Res<PlacementOrientation>,
+    hotbar_selection: HotbarSelection,
+    structure_defs: Res<Assets<StructureDef>>,
+    previews: Query<(Ref<ConstructionPreview>, &mut Footprint, &mut Sprite)>,
+) {
+    let Some(HotbarActionKind::PlaceStructure(handle)) = hotbar_selection.action() else {
+        return;
+    };
+
+    let Some(structure_def) = structure_defs.get(handle) else {
+        return;
+    };
+
+    let orientation = placement_orientation.0;
+
+    for (preview, mut footprint, mut sprite) in previews {
+        if !preview.is_added() && !placement_orientation.is_changed() {
+            continue;
+        }
+
+        *footprint = orientation.footprint(&structure_def.footprint);
+        sprite.flip_x = orientation.flips_sprite();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_turns_north_clockwise() {
+        let mut direction = Direction::North;
+
+        for _ in 0..4 {
+            assert_eq!(Orientation(direction).apply(NORTH), direction.as_ivec2());
+            assert_eq!(
+                Orientation(direction).apply(EAST),
+                direction.clockwise().as_ivec2()
+            );
+
+            direction = direction.clockwise();
+        }
+
+        assert_eq!(direction, Direction::North);
+    }
+
+    #[test]
+    fn test_faces_only_open_listed_sides() {
+        let footprint = Footprint(vec![IVec2::ZERO, IVec2::X]);
+        let faces = Faces {
+            inputs: Some(vec![NORTH]),
+            outputs: None,
+        };
+
+        assert!(faces.accepts_input_from(IVec2::ZERO, &footprint, IVec2::new(1, 1)));
+        assert!(faces.accepts_input_from(IVec2::ZERO, &footprint, IVec2::new(0, 1)));
+        assert!(!faces.accepts_input_from(IVec2::ZERO, &footprint, IVec2::new(2, 0)));
+        assert!(!faces.accepts_input_from(IVec2::ZERO, &footprint, IVec2::new(0, -1)));
+        assert!(faces.offers_output_to(IVec2::ZERO, &footprint, IVec2::new(0, -1)));
+    }
+
+    #[test]
+    fn test_faces_open_every_side_when_unset() {
+        let footprint = Footprint(vec![IVec2::ZERO]);
+        let faces = Faces::default();
+
+        for side in [NORTH, EAST, SOUTH, WEST] {
+            assert!(faces.accepts_input_from(IVec2::ZERO, &footprint, side));
+            assert!(faces.offers_output_to(IVec2::ZERO, &footprint, side));
+        }
+    }
+}
@@ -1,19 +1,39 @@
-use bevy::prelude::*;
+use std::collections::HashMap;
+
+use bevy::{asset::LoadedFolder, prelude::*};
 use bevy_aseprite_ultra::prelude::*;
+use serde::Deserialize;
 
-use crate::gameplay::{
-    FactorySystems,
-    hud::hotbar::{HotbarActionKind, HotbarSelection},
-    people::porting::Walkable,
-    sprite_sort::{YSortSprite, ZIndexSprite},
-    world::{
-        construction::{Constructions, StructureConstructed},
-        demolition::{Demolishable, Demolished},
-        tilemap::{TILE_OFFSET, TileClicked, coord::Coord},
+use crate::{
+    assets::{
+        indexing::{AssetIndexPlugin, Indexable},
+        loaders::toml::{FromToml, TomlAssetPlugin},
+        tracking::LoadResource,
+    },
+    gameplay::{
+        FactorySystems,
+        hud::hotbar::{HotbarActionKind, HotbarSelection},
+        inventory::prelude::*,
+        people::porting::Walkable,
+        player::Player,
+        sprite_sort::{YSortSprite, ZIndexSprite},
+        world::{
+            construction::{ConstructKind, Constructions, StructureConstructed},
+            demolition::{Demolishable, Demolished},
+            history::{History, HistoryStep, PlacedConstruct},
+            tilemap::{TILE_OFFSET, TileClicked, coord::Coord},
+        },
     },
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        TomlAssetPlugin::<PathDef>::extensions(&["path.toml"]),
+        AssetIndexPlugin::<PathDef>::default(),
+    ));
+
+    app.load_resource::<PathAssets>();
+
     app.add_systems(
         FixedUpdate,
         spawn_path
@@ -26,43 +46,183 @@ pub(super) fn plugin(app: &mut App) {
     app.add_observer(compute_sprite);
 }
 
+#[derive(Asset, Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct PathAssets {
+    pub manifest_folder: Handle<LoadedFolder>,
+}
+
+impl FromWorld for PathAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        Self {
+            manifest_folder: asset_server.load_folder("manifests/paths"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PathRaw {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    pub speed_multiplier: f32,
+    #[serde(default = "default_capacity")]
+    pub capacity: u32,
+    #[serde(default)]
+    pub cost: HashMap<String, u32>,
+    #[serde(default)]
+    pub bridge: bool,
+    /// Colour multiplied into the sprite, in sRGB. Tells surfaces sharing a sprite sheet apart.
+    pub tint: Option<[f32; 3]>,
+}
+
+fn default_capacity() -> u32 {
+    4
+}
+
+/// Surface type of a path segment
+#[derive(Asset, Reflect, Debug)]
+pub struct PathDef {
+    pub id: String,
+    pub name: String,
+    /// Aseprite file containing one slice per segment shape
+    pub sprite: String,
+    /// Multiplier applied to the speed of porters walking on the surface
+    pub speed_multiplier: f32,
+    /// Number of porters that fit on a tile before others have to queue
+    pub capacity: u32,
+    pub cost: HashMap<Handle<ItemDef>, u32>,
+    /// Whether the segment may be built over rivers
+    pub bridge: bool,
+    pub tint: Color,
+}
+
+impl FromToml for PathDef {
+    type Raw = PathRaw;
+
+    fn from_toml(raw: Self::Raw, load_context: &mut bevy::asset::LoadContext) -> Self {
+        Self {
+            id: raw.id,
+            name: raw.name,
+            sprite: raw.sprite,
+            speed_multiplier: raw.speed_multiplier,
+            capacity: raw.capacity,
+            bridge: raw.bridge,
+            tint: raw
+                .tint
+                .map(|[r, g, b]| Color::srgb(r, g, b))
+                .unwrap_or(Color::WHITE),
+            cost: raw
+                .cost
+                .iter()
+                .map(|(key, val)| {
+                    (
+                        load_context.load(format!("manifests/items/{key}.item.toml")),
+                        *val,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Indexable for PathDef {
+    fn index(&self) -> &String {
+        &self.id
+    }
+}
+
+/// A placed path segment and the surface it is built from
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Walkable, Demolishable)]
+pub struct PathSegment(pub Handle<PathDef>);
+
 fn spawn_path(
     mut tile_clicks: MessageReader<TileClicked>,
     mut path_updates: MessageWriter<StructureConstructed>,
     hotbar_selection: HotbarSelection,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    path_defs: Res<Assets<PathDef>>,
     mut constructions: ResMut<Constructions>,
+    player: Single<Entity, With<Player>>,
+    inventory: Query<&Inventory>,
+    mut stacks: Query<&mut ItemStack>,
+    mut history: ResMut<History>,
 ) {
+    let mut placed = Vec::new();
+
     for TileClicked(coord) in tile_clicks.read() {
-        let Some(action) = hotbar_selection.action() else {
+        let Some(HotbarActionKind::PlacePath(handle)) = hotbar_selection.action() else {
+            continue;
+        };
+
+        let Some(path_def) = path_defs.get(handle) else {
             continue;
         };
 
-        if !matches!(action, HotbarActionKind::PlacePath) {
+        if constructions.contains_key(&coord.0) {
             continue;
         }
 
+        if !can_afford(*player, &path_def.cost, &inventory, &stacks) {
+            continue;
+        }
+
+        spend(*player, &path_def.cost, &inventory, &mut stacks);
+
         let entity = commands
-            .spawn((
-                Name::new("Path"),
-                Walkable,
-                Coord(IVec2::new(coord.x, coord.y)),
-                Sprite::sized(TILE_OFFSET),
-                AseSlice {
-                    aseprite: asset_server.load("sprites/logistics/path_segments.aseprite"),
-                    name: "C".into(),
-                },
-                YSortSprite,
-                ZIndexSprite(9),
-                Demolishable,
+            .spawn(path_segment(
+                path_def,
+                handle.clone(),
+                coord.0,
+                &asset_server,
             ))
             .id();
 
         constructions.insert(coord.xy(), entity);
 
         path_updates.write(StructureConstructed(entity));
+
+        placed.push(PlacedConstruct::new(
+            coord.0,
+            ConstructKind::Path(handle.clone()),
+        ));
     }
+
+    if !placed.is_empty() {
+        history.record(HistoryStep::Constructs {
+            placed,
+            removed: Vec::new(),
+        });
+    }
+}
+
+/// Segment of the given surface, its sprite picked once its neighbours are known
+pub fn path_segment(
+    path_def: &PathDef,
+    handle: Handle<PathDef>,
+    coord: IVec2,
+    asset_server: &AssetServer,
+) -> impl Bundle {
+    (
+        Name::new(path_def.name.clone()),
+        PathSegment(handle),
+        Coord(coord),
+        Sprite {
+            color: path_def.tint,
+            ..Sprite::sized(TILE_OFFSET)
+        },
+        AseSlice {
+            aseprite: asset_server.load(&path_def.sprite),
+            name: "C".into(),
+        },
+        YSortSprite,
+        ZIndexSprite(9),
+    )
 }
 
 fn pick_path_sprite(
@@ -113,31 +273,32 @@ pub struct ComputePathSegmentSprite {
     pub entity: Entity,
 }
 
+/// Picks the segment shape from the neighbouring constructions. Segments of different surfaces
+/// join each other, each drawing its half of the connection from its own sprite set.
 fn compute_sprite(
     compute_path_segment_sprite: On<ComputePathSegmentSprite>,
-    mut path_query: Query<(&Coord, &mut AseSlice), With<Walkable>>,
+    mut path_query: Query<(&Coord, &mut AseSlice), With<PathSegment>>,
     constructions: Res<Constructions>,
 ) {
     let Ok((coord, mut aseslice)) = path_query.get_mut(compute_path_segment_sprite.entity) else {
         return;
     };
 
+    aseslice.name = segment_slice_name(coord.0, |c| constructions.contains_key(&c));
+}
+
+/// Name of the sprite slice for a segment, given which neighbouring tiles it connects to
+pub fn segment_slice_name(coord: IVec2, connects: impl Fn(IVec2) -> bool) -> String {
     let dirs = ['N', 'E', 'S', 'W'];
 
     let name = [IVec2::Y, IVec2::X, IVec2::NEG_Y, IVec2::NEG_X]
         .into_iter()
-        .map(|c| coord.0 + c)
+        .map(|c| coord + c)
         .enumerate()
-        .flat_map(|(i, c)| {
-            if constructions.contains_key(&c) {
-                Some(dirs[i])
-            } else {
-                None
-            }
-        })
+        .flat_map(|(i, c)| if connects(c) { Some(dirs[i]) } else { None })
         .collect::<String>();
 
-    let name = if name.is_empty() {
+    if name.is_empty() {
         String::from("C")
     } else if name == "W" || name == "E" {
         String::from("EW")
@@ -145,7 +306,35 @@ fn compute_sprite(
         String::from("NS")
     } else {
         name
-    };
+    }
+}
+
+/// Speed multiplier of the surface at the given coordinate. Tiles without a path segment, such
+/// as the structure a porter departs from, walk at normal speed.
+pub fn surface_speed_multiplier(
+    coord: &Coord,
+    constructions: &Constructions,
+    segments: &Query<&PathSegment>,
+    path_defs: &Assets<PathDef>,
+) -> f32 {
+    constructions
+        .get(&coord.0)
+        .and_then(|entity| segments.get(*entity).ok())
+        .and_then(|segment| path_defs.get(&segment.0))
+        .map_or(1.0, |path_def| path_def.speed_multiplier)
+}
 
-    aseslice.name = name;
+/// Porter capacity of the tile at the given coordinate, or `None` for tiles without a path
+/// segment which fit any number of porters
+pub fn tile_capacity(
+    coord: &IVec2,
+    constructions: &Constructions,
+    segments: &Query<&PathSegment>,
+    path_defs: &Assets<PathDef>,
+) -> Option<u32> {
+    constructions
+        .get(coord)
+        .and_then(|entity| segments.get(*entity).ok())
+        .and_then(|segment| path_defs.get(&segment.0))
+        .map(|path_def| path_def.capacity)
 }
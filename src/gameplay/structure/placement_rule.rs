@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::gameplay::structure::{footprint::Footprint, range::Range};
+
+/// Condition on the surroundings a structure's manifest requires for it to be placed
+#[derive(Reflect, Deserialize, Debug, Clone, PartialEq)]
+pub enum PlacementRule {
+    /// A deposit within the given manhattan distance of the structure's origin tile
+    DepositInRange(i32),
+    /// A path segment sharing an edge with the structure
+    PathAdjacent,
+    /// Every tile of the structure lies in one of the biomes, by id
+    Biomes(Vec<String>),
+}
+
+/// What occupies a tile, as far as placement rules are concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileContent {
+    Empty,
+    Deposit,
+    Path,
+    Other,
+}
+
+impl PlacementRule {
+    /// Whether the rule holds for a structure covering `footprint` at `origin`, given what occupies
+    /// each tile and the id of the biome it lies in
+    pub fn satisfied<'a>(
+        &self,
+        origin: IVec2,
+        footprint: &Footprint,
+        content: impl Fn(IVec2) -> TileContent,
+        biome: impl Fn(IVec2) -> Option<&'a str>,
+    ) -> bool {
+        match self {
+            PlacementRule::DepositInRange(range) => Range::Diamond(*range)
+                .iter(origin)
+                .any(|tile| content(tile) == TileContent::Deposit),
+            PlacementRule::PathAdjacent => footprint
+                .neighbours(origin)
+                .into_iter()
+                .any(|tile| content(tile) == TileContent::Path),
+            PlacementRule::Biomes(biomes) => footprint.tiles(origin).all(|tile| {
+                biome(tile).is_some_and(|id| biomes.iter().any(|allowed| allowed == id))
+            }),
+        }
+    }
+}
+
+/// Reason a construct can't be placed on a tile
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub enum PlacementFailure {
+    Occupied,
+    /// Tiles hidden by the fog of war can't be built on
+    Unexplored,
+    /// Only bridges can be built over rivers
+    Water,
+    /// Junctions go on top of path segments
+    NotOnPath,
+    Rule(PlacementRule),
+}
+
+impl PlacementFailure {
+    pub fn describe(&self) -> String {
+        match self {
+            PlacementFailure::Occupied => "Tile is occupied".into(),
+            PlacementFailure::Unexplored => "Tile is unexplored".into(),
+            PlacementFailure::Water => "Needs a bridge to cross water".into(),
+            PlacementFailure::NotOnPath => "Must be placed on a path".into(),
+            PlacementFailure::Rule(PlacementRule::DepositInRange(range)) => {
+                format!("Needs a deposit within {range} tiles")
+            }
+            PlacementFailure::Rule(PlacementRule::PathAdjacent) => "Needs an adjacent path".into(),
+            PlacementFailure::Rule(PlacementRule::Biomes(biomes)) => {
+                format!("Must be built in {}", biomes.join(" or "))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_check_surroundings() {
+        let content = |tile: IVec2| match tile {
+            IVec2 { x: 3, y: 0 } => TileContent::Deposit,
+            IVec2 { x: 0, y: 2 } => TileContent::Path,
+            _ => TileContent::Empty,
+        };
+
+        let biome = |tile: IVec2| Some(if tile.y > 0 { "swamp" } else { "grassland" });
+
+        let footprint = Footprint(vec![IVec2::ZERO, IVec2::Y]);
+        let satisfied = |rule: PlacementRule, footprint: &Footprint| {
+            rule.satisfied(IVec2::ZERO, footprint, content, biome)
+        };
+
+        assert!(satisfied(PlacementRule::DepositInRange(3), &footprint));
+        assert!(!satisfied(PlacementRule::DepositInRange(2), &footprint));
+        assert!(satisfied(PlacementRule::PathAdjacent, &footprint));
+        assert!(!satisfied(
+            PlacementRule::PathAdjacent,
+            &Footprint::default()
+        ));
+
+        let grassland = PlacementRule::Biomes(vec!["grassland".into()]);
+        assert!(satisfied(grassland.clone(), &Footprint::default()));
+        assert!(!satisfied(grassland, &footprint));
+    }
+}
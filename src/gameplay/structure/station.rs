@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+use crate::{
+    gameplay::{
+        structure::{Structure, assets::StructureDef},
+        world::construction::StructureConstructed,
+    },
+    screens::Screen,
+};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<StationsNamed>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_stations_named);
+
+    app.add_systems(
+        Update,
+        name_new_stations.run_if(on_message::<StructureConstructed>),
+    );
+}
+
+/// Named stop for carts
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Station {
+    pub name: String,
+}
+
+/// Number of stations named so far, never lowered so demolished stations don't hand their name on
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct StationsNamed(pub u32);
+
+fn reset_stations_named(mut stations_named: ResMut<StationsNamed>) {
+    *stations_named = StationsNamed::default();
+}
+
+fn name_new_stations(
+    mut structures_constructed: MessageReader<StructureConstructed>,
+    structures: Query<&Structure>,
+    structure_defs: Res<Assets<StructureDef>>,
+    mut stations_named: ResMut<StationsNamed>,
+    mut commands: Commands,
+) {
+    for StructureConstructed(entity) in structures_constructed.read() {
+        let is_station = structures
+            .get(*entity)
+            .ok()
+            .and_then(|structure| structure_defs.get(&structure.0))
+            .is_some_and(|structure_def| structure_def.station);
+
+        if !is_station {
+            continue;
+        }
+
+        stations_named.0 += 1;
+
+        commands.entity(*entity).insert(Station {
+            name: format!("Station {}", stations_named.0),
+        });
+    }
+}
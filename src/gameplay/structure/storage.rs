@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+
+use crate::gameplay::{
+    FactorySystems,
+    inventory::prelude::*,
+    structure::{Structure, assets::StructureDef},
+    world::construction::StructureConstructed,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedUpdate,
+        (
+            spawn_storage_slots.run_if(on_message::<StructureConstructed>),
+            sync_storage_drop_offs,
+        )
+            .chain()
+            .in_set(FactorySystems::Work),
+    );
+}
+
+/// Structure holding surplus items in general purpose slots
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+#[require(Inventory)]
+pub struct Storage {
+    /// Items the storage takes in, any item when empty
+    pub filters: Vec<ItemFilter>,
+}
+
+impl Storage {
+    pub fn toggle_filter(&mut self, filter: ItemFilter) {
+        if let Some(index) = self.filters.iter().position(|f| *f == filter) {
+            self.filters.remove(index);
+        } else {
+            self.filters.push(filter);
+        }
+    }
+}
+
+/// Slot of a storage, able to hold any item up to its stack size
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct StorageSlot;
+
+fn spawn_storage_slots(
+    mut structures_constructed: MessageReader<StructureConstructed>,
+    structures: Query<&Structure>,
+    structure_defs: Res<Assets<StructureDef>>,
+    mut commands: Commands,
+) {
+    for StructureConstructed(entity) in structures_constructed.read() {
+        let Some(slots) = structures
+            .get(*entity)
+            .ok()
+            .and_then(|structure| structure_defs.get(&structure.0))
+            .and_then(|structure_def| structure_def.storage_slots)
+        else {
+            continue;
+        };
+
+        commands.entity(*entity).insert(Storage::default());
+
+        for _ in 0..slots {
+            commands.spawn((empty_slot(*entity), StorageSlot, Pickup));
+        }
+    }
+}
+
+/// Keeps the drop-off of each storage slot in line with its contents. Empty slots take anything
+/// passing the filters, partially filled slots only take more of the same and full slots take
+/// nothing.
+fn sync_storage_drop_offs(
+    storages: Query<(Entity, &Storage)>,
+    inventory: Query<&Inventory>,
+    slots: Query<(Option<&ItemStack>, Option<&DropOff>), With<StorageSlot>>,
+    item_defs: Res<Assets<ItemDef>>,
+    mut commands: Commands,
+) {
+    for (entity, storage) in storages {
+        let accepted = DropOff::Filter(storage.filters.clone());
+
+        for slot in inventory.iter_descendants(entity) {
+            let Ok((stack, drop_off)) = slots.get(slot) else {
+                continue;
+            };
+
+            let desired = match stack {
+                Some(stack) if stack.quantity > 0 => item_defs
+                    .get(&stack.item)
+                    .filter(|item_def| stack.quantity < item_def.stack_size)
+                    .filter(|_| accepted.accepts(&stack.item, &item_defs))
+                    .map(|_| DropOff::Item(stack.item.clone())),
+                _ => Some(DropOff::Filter(storage.filters.clone())),
+            };
+
+            if drop_off == desired.as_ref() {
+                continue;
+            }
+
+            match desired {
+                Some(drop_off) => commands.entity(slot).insert(drop_off),
+                None => commands.entity(slot).remove::<DropOff>(),
+            };
+        }
+    }
+}
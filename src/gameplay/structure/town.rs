@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+
+use bevy::{asset::LoadedFolder, prelude::*, sprite::Anchor};
+use serde::Deserialize;
+
+use crate::{
+    assets::{
+        indexing::{AssetIndexPlugin, IndexMap, Indexable},
+        loaders::toml::{FromToml, TomlAssetPlugin},
+        tracking::LoadResource,
+    },
+    gameplay::{
+        inventory::prelude::*,
+        people::{naming::NameManager, person},
+        player::Player,
+        sprite_sort::{YSortSprite, ZIndexSprite},
+        structure::interactable::{Interact, Interactable},
+        world::{
+            construction::Constructions,
+            fog::FogOfWar,
+            generation::{ChunkPlan, PlannedConstruct, WorldGen},
+            tilemap::{TILE_SIZE, chunk::ChunkLoaded, coord::Coord},
+        },
+    },
+    screens::Screen,
+    widgets::tooltip::{HideTooltip, ShowTooltip, TooltipSource},
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins((
+        TomlAssetPlugin::<TownDef>::extensions(&["town.toml"]),
+        AssetIndexPlugin::<TownDef>::default(),
+    ));
+    app.load_resource::<TownAssets>();
+
+    app.init_resource::<Towns>();
+    app.init_resource::<HoveredTown>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_towns);
+
+    app.add_observer(spawn_towns);
+    app.add_observer(recruit);
+    app.add_observer(on_town_hover);
+    app.add_observer(on_town_leave);
+
+    app.add_systems(
+        Update,
+        (show_town_mood, refresh_town_tooltip).run_if(resource_changed::<Towns>),
+    );
+}
+
+#[derive(Deserialize)]
+pub struct TownRaw {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    /// Smallest and largest number of townsfolk the town is founded with
+    pub population: [u32; 2],
+    #[serde(default)]
+    pub recruitment_cost: HashMap<String, u32>,
+}
+
+#[derive(Asset, Reflect, Debug)]
+pub struct TownDef {
+    pub id: String,
+    pub name: String,
+    pub sprite: String,
+    pub population: [u32; 2],
+    /// Items paid for each townsperson recruited while the town is welcoming
+    pub recruitment_cost: HashMap<Handle<ItemDef>, u32>,
+}
+
+impl Indexable for TownDef {
+    fn index(&self) -> &String {
+        &self.id
+    }
+}
+
+impl FromToml for TownDef {
+    type Raw = TownRaw;
+
+    fn from_toml(raw: Self::Raw, load_context: &mut bevy::asset::LoadContext) -> Self {
+        Self {
+            id: raw.id,
+            name: raw.name,
+            sprite: raw.sprite,
+            population: raw.population,
+            recruitment_cost: raw
+                .recruitment_cost
+                .iter()
+                .map(|(key, val)| {
+                    (
+                        load_context.load(format!("manifests/items/{key}.item.toml")),
+                        *val,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Asset, Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct TownAssets {
+    manifest_folder: Handle<LoadedFolder>,
+}
+
+impl FromWorld for TownAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+
+        Self {
+            manifest_folder: assets.load_folder("manifests/towns"),
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Interactable)]
+pub struct Town(pub Handle<TownDef>);
+
+/// Townsfolk of a town, both those still living there and those who have gone missing
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub struct Populace {
+    /// Townsfolk the town was founded with
+    pub founded: u32,
+    /// Townsfolk not yet recruited
+    pub remaining: u32,
+}
+
+impl Populace {
+    pub fn new(population: u32) -> Self {
+        Self {
+            founded: population,
+            remaining: population,
+        }
+    }
+
+    pub fn missing(&self) -> u32 {
+        self.founded - self.remaining
+    }
+
+    pub fn mood(&self) -> Mood {
+        let missing = self.missing() as f32 / self.founded.max(1) as f32;
+
+        if self.remaining == 0 || missing >= 0.75 {
+            Mood::Hostile
+        } else if missing >= 0.5 {
+            Mood::Fearful
+        } else if missing >= 0.25 {
+            Mood::Uneasy
+        } else {
+            Mood::Welcoming
+        }
+    }
+}
+
+/// How a town feels about the player, souring as its townsfolk go missing
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mood {
+    Welcoming,
+    Uneasy,
+    Fearful,
+    /// Refuses to give up anyone else
+    Hostile,
+}
+
+impl Mood {
+    /// Factor the recruitment cost is multiplied by, if the town lets anyone be recruited at all
+    pub fn cost_multiplier(&self) -> Option<u32> {
+        match self {
+            Mood::Welcoming => Some(1),
+            Mood::Uneasy => Some(2),
+            Mood::Fearful => Some(3),
+            Mood::Hostile => None,
+        }
+    }
+
+    fn tint(&self) -> Color {
+        match self {
+            Mood::Welcoming => Color::WHITE,
+            Mood::Uneasy => Color::srgb(1.0, 0.9, 0.7),
+            Mood::Fearful => Color::srgb(0.9, 0.7, 0.6),
+            Mood::Hostile => Color::srgb(0.7, 0.4, 0.4),
+        }
+    }
+}
+
+/// Populace of every town found so far, by the town's coordinate, kept apart from the town
+/// entities so it outlives unloaded chunks
+#[derive(Resource, Reflect, Debug, Default, Deref, DerefMut)]
+#[reflect(Resource)]
+pub struct Towns(pub HashMap<IVec2, Populace>);
+
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+struct HoveredTown(Option<Entity>);
+
+fn reset_towns(mut towns: ResMut<Towns>) {
+    *towns = Towns::default();
+}
+
+/// Spawns the towns generation planned for a chunk, as populated as they were left
+fn spawn_towns(
+    chunk_loaded: On<ChunkLoaded>,
+    plans: Query<&ChunkPlan>,
+    mut commands: Commands,
+    world_gen: Res<WorldGen>,
+    town_index: Res<IndexMap<TownDef>>,
+    town_defs: Res<Assets<TownDef>>,
+    asset_server: Res<AssetServer>,
+    fog: Res<FogOfWar>,
+    mut towns: ResMut<Towns>,
+    mut constructions: ResMut<Constructions>,
+) {
+    let Ok(plan) = plans.get(chunk_loaded.chunk) else {
+        return;
+    };
+
+    for (_, coord, tile) in plan.iter() {
+        let Some(PlannedConstruct::Town { town, population }) = tile.construct else {
+            continue;
+        };
+
+        if constructions.contains_key(&coord) {
+            continue;
+        }
+
+        let Some(town_id) = world_gen
+            .spec
+            .towns
+            .get(town)
+            .and_then(|spec| town_index.get(&spec.id))
+        else {
+            continue;
+        };
+
+        let Some(town_def) = town_defs.get(*town_id) else {
+            continue;
+        };
+
+        let populace = *towns.entry(coord).or_insert(Populace::new(population));
+
+        let visibility = if fog.is_revealed(coord) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        let entity = commands
+            .spawn((
+                Name::new(town_def.name.clone()),
+                visibility,
+                Town(asset_server.get_id_handle(*town_id).unwrap()),
+                Coord(coord),
+                Anchor(Vec2::new(0.0, -0.25)),
+                YSortSprite,
+                ZIndexSprite(10),
+                Sprite {
+                    image: asset_server.load(&town_def.sprite),
+                    custom_size: Vec2::new(TILE_SIZE.x, TILE_SIZE.y).into(),
+                    color: populace.mood().tint(),
+                    ..default()
+                },
+            ))
+            .id();
+
+        constructions.insert(coord, entity);
+    }
+}
+
+/// Recruits a townsperson into the workforce, paying more the more the town has already lost
+fn recruit(
+    interact: On<Interact>,
+    town_query: Query<(&Town, &Coord)>,
+    town_defs: Res<Assets<TownDef>>,
+    mut towns: ResMut<Towns>,
+    fog: Res<FogOfWar>,
+    player: Single<Entity, With<Player>>,
+    inventory: Query<&Inventory>,
+    mut stacks: Query<&mut ItemStack>,
+    mut name_manager: ResMut<NameManager>,
+    mut commands: Commands,
+) {
+    let Ok((town, coord)) = town_query.get(interact.entity) else {
+        return;
+    };
+
+    if !fog.is_revealed(coord.0) {
+        return;
+    }
+
+    let (Some(town_def), Some(populace)) = (town_defs.get(&town.0), towns.get_mut(&coord.0)) else {
+        return;
+    };
+
+    let Some(multiplier) = populace.mood().cost_multiplier() else {
+        return;
+    };
+
+    let cost = recruitment_cost(town_def, multiplier);
+    if !can_afford(*player, &cost, &inventory, &stacks) {
+        return;
+    }
+
+    spend(*player, &cost, &inventory, &mut stacks);
+    populace.remaining -= 1;
+
+    commands.spawn(person(&mut name_manager));
+}
+
+fn recruitment_cost(town_def: &TownDef, multiplier: u32) -> HashMap<Handle<ItemDef>, u32> {
+    town_def
+        .recruitment_cost
+        .iter()
+        .map(|(item, quantity)| (item.clone(), quantity * multiplier))
+        .collect()
+}
+
+fn show_town_mood(town_query: Query<(&Coord, &mut Sprite), With<Town>>, towns: Res<Towns>) {
+    for (coord, mut sprite) in town_query {
+        if let Some(populace) = towns.get(&coord.0) {
+            sprite.color = populace.mood().tint();
+        }
+    }
+}
+
+fn on_town_hover(
+    pointer_over: On<Pointer<Over>>,
+    town_query: Query<(), With<Town>>,
+    mut hovered_town: ResMut<HoveredTown>,
+    mut towns: ResMut<Towns>,
+) {
+    if town_query.contains(pointer_over.entity) {
+        hovered_town.0 = Some(pointer_over.entity);
+        // Shows the tooltip of the newly hovered town
+        towns.set_changed();
+    }
+}
+
+fn on_town_leave(
+    pointer_out: On<Pointer<Out>>,
+    mut hovered_town: ResMut<HoveredTown>,
+    mut commands: Commands,
+) {
+    if hovered_town.0 == Some(pointer_out.entity) {
+        hovered_town.0 = None;
+        commands.trigger(HideTooltip(TooltipSource::Town));
+    }
+}
+
+fn refresh_town_tooltip(
+    hovered_town: Res<HoveredTown>,
+    town_query: Query<(&Town, &Coord)>,
+    town_defs: Res<Assets<TownDef>>,
+    item_defs: Res<Assets<ItemDef>>,
+    towns: Res<Towns>,
+    mut commands: Commands,
+) {
+    let Some((town, coord)) = hovered_town.0.and_then(|town| town_query.get(town).ok()) else {
+        return;
+    };
+
+    let (Some(town_def), Some(populace)) = (town_defs.get(&town.0), towns.get(&coord.0)) else {
+        return;
+    };
+
+    let mood = populace.mood();
+
+    let mut lines = vec![
+        town_def.name.clone(),
+        format!(
+            "Population {} ({} missing)",
+            populace.remaining,
+            populace.missing()
+        ),
+        format!("Mood: {mood:?}"),
+    ];
+
+    match mood.cost_multiplier() {
+        Some(multiplier) => {
+            lines.push(String::from("Recruit for:"));
+
+            for (item, quantity) in recruitment_cost(town_def, multiplier) {
+                if let Some(item_def) = item_defs.get(&item) {
+                    lines.push(format!("{quantity} {}", item_def.name));
+                }
+            }
+        }
+        None => lines.push(String::from("Refuses to give up anyone else")),
+    }
+
+    commands.trigger(ShowTooltip {
+        source: TooltipSource::Town,
+        text: lines.join("\n"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mood_sours_as_townsfolk_go_missing() {
+        let mut populace = Populace::new(8);
+        assert_eq!(populace.mood(), Mood::Welcoming);
+
+        populace.remaining = 6;
+        assert_eq!(populace.mood(), Mood::Uneasy);
+
+        populace.remaining = 3;
+        assert_eq!(populace.mood(), Mood::Fearful);
+        assert_eq!(populace.mood().cost_multiplier(), Some(3));
+
+        populace.remaining = 2;
+        assert_eq!(populace.mood(), Mood::Hostile);
+        assert_eq!(populace.mood().cost_multiplier(), None);
+
+        assert_eq!(Populace::new(0).mood(), Mood::Hostile);
+    }
+}
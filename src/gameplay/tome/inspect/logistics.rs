@@ -0,0 +1,232 @@
+use bevy::{prelude::*, ui_widgets::observe};
+
+use crate::{
+    gameplay::{
+        inventory::prelude::*,
+        structure::junction::Junction,
+        tome::{
+            UITomeLeftPageRoot, UITomeRightPageRoot,
+            inspect::{InspectTabs, Inspected},
+            list_page,
+        },
+        world::tilemap::{EAST, NORTH, SOUTH, WEST},
+    },
+    widgets,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(InspectTabs::Logistics),
+        (spawn_junction_settings, spawn_drop_off_priorities),
+    );
+
+    app.add_systems(
+        Update,
+        (refresh_sorter_options, refresh_priority_labels).run_if(in_state(InspectTabs::Logistics)),
+    );
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+enum SorterOption {
+    Branch(IVec2),
+    Filter(Option<ItemFilter>),
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct OptionLabel(String);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct PriorityLabel(Entity);
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct PriorityButton {
+    slot: Entity,
+    delta: i32,
+}
+
+fn spawn_junction_settings(
+    mut commands: Commands,
+    left_page: Single<Entity, With<UITomeLeftPageRoot>>,
+    inspected: Res<Inspected>,
+    junctions: Query<&Junction>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            DespawnOnExit(InspectTabs::Logistics),
+            ChildOf(*left_page),
+        ))
+        .id();
+
+    match junctions.get(inspected.0) {
+        Ok(Junction::Sorter { .. }) => {
+            commands.spawn((Text::new("Branch"), ChildOf(id)));
+
+            for (dir, name) in [
+                (NORTH, "North"),
+                (EAST, "East"),
+                (SOUTH, "South"),
+                (WEST, "West"),
+            ] {
+                commands.spawn(sorter_option(id, SorterOption::Branch(dir), name));
+            }
+
+            commands.spawn((Text::new("Sends"), ChildOf(id)));
+
+            commands.spawn(sorter_option(id, SorterOption::Filter(None), "Nothing"));
+
+            for tag in ItemTag::ALL {
+                commands.spawn(sorter_option(
+                    id,
+                    SorterOption::Filter(Some(ItemFilter::Tag(tag))),
+                    &format!("Tag: {tag:?}"),
+                ));
+            }
+
+            for taxonomy in Taxonomy::ALL {
+                commands.spawn(sorter_option(
+                    id,
+                    SorterOption::Filter(Some(ItemFilter::Taxonomy(taxonomy.clone()))),
+                    &format!("Taxonomy: {taxonomy:?}"),
+                ));
+            }
+        }
+        Ok(Junction::Splitter { .. }) => {
+            commands.spawn((Text::new("Alternates between branches"), ChildOf(id)));
+        }
+        Err(_) => {
+            commands.spawn((Text::new("Not a junction"), ChildOf(id)));
+        }
+    }
+}
+
+fn sorter_option(list: Entity, option: SorterOption, label: &str) -> impl Bundle {
+    (
+        Text::default(),
+        option,
+        OptionLabel(label.to_string()),
+        ChildOf(list),
+        observe(on_sorter_option_click),
+    )
+}
+
+fn on_sorter_option_click(
+    click: On<Pointer<Click>>,
+    inspected: Res<Inspected>,
+    options: Query<&SorterOption>,
+    mut junctions: Query<&mut Junction>,
+) {
+    let Ok(option) = options.get(click.entity) else {
+        return;
+    };
+
+    let Ok(mut junction) = junctions.get_mut(inspected.0) else {
+        return;
+    };
+
+    let Junction::Sorter { filter, branch } = junction.as_mut() else {
+        return;
+    };
+
+    match option {
+        SorterOption::Branch(dir) => *branch = *dir,
+        SorterOption::Filter(option) => *filter = option.clone(),
+    }
+}
+
+fn refresh_sorter_options(
+    inspected: Res<Inspected>,
+    junctions: Query<&Junction>,
+    options: Query<(&SorterOption, &OptionLabel, &mut Text)>,
+) {
+    let Ok(Junction::Sorter { filter, branch }) = junctions.get(inspected.0) else {
+        return;
+    };
+
+    for (option, label, mut text) in options {
+        let selected = match option {
+            SorterOption::Branch(dir) => dir == branch,
+            SorterOption::Filter(option) => option == filter,
+        };
+
+        let marker = if selected { "(x)" } else { "( )" };
+
+        text.0 = format!("{marker} {}", label.0);
+    }
+}
+
+fn spawn_drop_off_priorities(
+    mut commands: Commands,
+    right_page: Single<Entity, With<UITomeRightPageRoot>>,
+    inspected: Res<Inspected>,
+    inventory: Query<&Inventory>,
+    drop_offs: Query<(), With<DropOff>>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            DespawnOnExit(InspectTabs::Logistics),
+            ChildOf(*right_page),
+        ))
+        .id();
+
+    for slot in inventory
+        .iter_descendants(inspected.0)
+        .filter(|slot| drop_offs.contains(*slot))
+    {
+        commands.spawn((
+            Node {
+                column_gap: px(8.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ChildOf(id),
+            children![
+                widgets::item_plate(slot),
+                priority_button(slot, -1),
+                (PriorityLabel(slot), Text::default()),
+                priority_button(slot, 1),
+            ],
+        ));
+    }
+}
+
+fn priority_button(slot: Entity, delta: i32) -> impl Bundle {
+    (
+        Text::new(if delta < 0 { "-" } else { "+" }),
+        PriorityButton { slot, delta },
+        observe(on_priority_button_click),
+    )
+}
+
+fn on_priority_button_click(
+    click: On<Pointer<Click>>,
+    buttons: Query<&PriorityButton>,
+    priorities: Query<&DropOffPriority>,
+    mut commands: Commands,
+) {
+    let Ok(PriorityButton { slot, delta }) = buttons.get(click.entity) else {
+        return;
+    };
+
+    let priority = priorities.get(*slot).copied().unwrap_or_default();
+
+    commands
+        .entity(*slot)
+        .insert(DropOffPriority(priority.0 + delta));
+}
+
+fn refresh_priority_labels(
+    labels: Query<(&PriorityLabel, &mut Text)>,
+    priorities: Query<&DropOffPriority>,
+) {
+    for (PriorityLabel(slot), mut text) in labels {
+        let priority = priorities.get(*slot).copied().unwrap_or_default();
+
+        text.0 = format!("Priority {}", priority.0);
+    }
+}
@@ -3,8 +3,12 @@ use bevy::prelude::*;
 use crate::gameplay::tome::{TomeMenu, tome_plugin::TomePlugin};
 
 pub mod forager_management;
+pub mod logistics;
 pub mod porter_management;
 pub mod recipe_select;
+pub mod schedule;
+pub mod station;
+pub mod storage_filters;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins(TomePlugin::<InspectTabs> {
@@ -13,13 +17,21 @@ pub(super) fn plugin(app: &mut App) {
             ("Recipe", InspectTabs::RecipeSelect),
             ("Porters", InspectTabs::PorterManagement),
             ("Foragers", InspectTabs::ForagerManagement),
+            ("Storage", InspectTabs::StorageFilters),
+            ("Logistics", InspectTabs::Logistics),
+            ("Station", InspectTabs::Station),
+            ("Schedule", InspectTabs::Schedule),
         ],
     });
 
     app.add_plugins((
         forager_management::plugin,
+        logistics::plugin,
         porter_management::plugin,
         recipe_select::plugin,
+        schedule::plugin,
+        station::plugin,
+        storage_filters::plugin,
     ));
 
     app.add_observer(on_inspect);
@@ -38,6 +50,10 @@ pub enum InspectTabs {
     RecipeSelect,
     PorterManagement,
     ForagerManagement,
+    StorageFilters,
+    Logistics,
+    Station,
+    Schedule,
 }
 
 #[derive(Resource, Reflect, Debug)]
@@ -5,13 +5,17 @@ use crate::{
         inventory::prelude::*,
         recipe::{
             assets::Recipe,
-            select::{RecipeChanged, SelectRecipe},
+            select::{RecipeChanged, SelectRecipe, SelectedRecipe},
         },
         tome::{
             UITomeLeftPageRoot, UITomeRightPageRoot,
             inspect::{InspectTabs, Inspected},
             list_page,
         },
+        world::{
+            history::{History, HistoryStep},
+            tilemap::coord::Coord,
+        },
     },
     widgets,
 };
@@ -66,11 +70,25 @@ fn on_recipe_select(
     mut commands: Commands,
     inspected: Res<Inspected>,
     recipe_badges: Query<&RecipeButton>,
+    selected_recipes: Query<(&Coord, Option<&SelectedRecipe>)>,
+    mut history: ResMut<History>,
 ) {
     let Ok(recipe) = recipe_badges.get(click.entity) else {
         return;
     };
 
+    if let Ok((coord, selected_recipe)) = selected_recipes.get(inspected.0) {
+        let from = selected_recipe.map(|selected| selected.id());
+
+        if from != Some(recipe.0) {
+            history.record(HistoryStep::Recipe {
+                coord: coord.0,
+                from,
+                to: Some(recipe.0),
+            });
+        }
+    }
+
     commands.trigger(SelectRecipe {
         entity: inspected.0,
         recipe: recipe.0,
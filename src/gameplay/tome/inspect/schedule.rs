@@ -0,0 +1,268 @@
+use bevy::{prelude::*, ui_widgets::observe};
+
+use crate::{
+    gameplay::{
+        cart::{CargoRule, Cart, CartSchedule, CartStop},
+        inventory::prelude::*,
+        structure::station::Station,
+        tome::{
+            UITomeLeftPageRoot, UITomeRightPageRoot,
+            inspect::{InspectTabs, Inspected},
+            list_page,
+        },
+    },
+    widgets,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(InspectTabs::Schedule),
+        (spawn_schedule_list, spawn_cart_contents),
+    );
+
+    app.add_systems(
+        Update,
+        refresh_schedule_list.run_if(in_state(InspectTabs::Schedule)),
+    );
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ScheduleList;
+
+#[derive(Reflect, Debug, Clone, Copy)]
+enum ScheduleAction {
+    CycleStation,
+    CycleUnload,
+    CycleLoad,
+    Remove,
+    Add,
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ScheduleButton {
+    stop: usize,
+    action: ScheduleAction,
+}
+
+/// Rules a stop can cycle through, with their labels
+fn cargo_rule_options(
+    item_defs: &Assets<ItemDef>,
+    asset_server: &AssetServer,
+) -> Vec<(CargoRule, String)> {
+    let mut options = vec![
+        (CargoRule::Nothing, String::from("Nothing")),
+        (CargoRule::Everything, String::from("Everything")),
+    ];
+
+    for tag in ItemTag::ALL {
+        options.push((
+            CargoRule::Matching(ItemFilter::Tag(tag)),
+            format!("Tag: {tag:?}"),
+        ));
+    }
+
+    for taxonomy in Taxonomy::ALL {
+        options.push((
+            CargoRule::Matching(ItemFilter::Taxonomy(taxonomy.clone())),
+            format!("Taxonomy: {taxonomy:?}"),
+        ));
+    }
+
+    for (asset_id, item_def) in item_defs.iter() {
+        if let Some(handle) = asset_server.get_id_handle(asset_id) {
+            options.push((
+                CargoRule::Matching(ItemFilter::Item(handle)),
+                item_def.name.clone(),
+            ));
+        }
+    }
+
+    options
+}
+
+fn spawn_schedule_list(
+    mut commands: Commands,
+    left_page: Single<Entity, With<UITomeLeftPageRoot>>,
+    inspected: Res<Inspected>,
+    carts: Query<(), With<Cart>>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            DespawnOnExit(InspectTabs::Schedule),
+            ChildOf(*left_page),
+        ))
+        .id();
+
+    if carts.contains(inspected.0) {
+        commands.entity(id).insert(ScheduleList);
+    } else {
+        commands.spawn((Text::new("Not a cart"), ChildOf(id)));
+    }
+}
+
+fn refresh_schedule_list(
+    inspected: Res<Inspected>,
+    schedules: Query<Ref<CartSchedule>>,
+    list: Single<(Entity, Ref<ScheduleList>)>,
+    stations: Query<&Station>,
+    item_defs: Res<Assets<ItemDef>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    let (list, marker) = list.into_inner();
+
+    let Ok(schedule) = schedules.get(inspected.0) else {
+        return;
+    };
+
+    if !schedule.is_changed() && !marker.is_added() {
+        return;
+    }
+
+    commands.entity(list).despawn_children();
+
+    let rules = cargo_rule_options(&item_defs, &asset_server);
+    let rule_label = |rule: &CargoRule| {
+        rules
+            .iter()
+            .find(|(option, _)| option == rule)
+            .map_or("?", |(_, label)| label.as_str())
+            .to_string()
+    };
+
+    for (index, stop) in schedule.stops.iter().enumerate() {
+        let station = stations
+            .get(stop.station)
+            .map_or("Demolished station", |station| station.name.as_str());
+
+        for (label, action) in [
+            (
+                format!("{}. {station}", index + 1),
+                ScheduleAction::CycleStation,
+            ),
+            (
+                format!("Unload: {}", rule_label(&stop.unload)),
+                ScheduleAction::CycleUnload,
+            ),
+            (
+                format!("Load: {}", rule_label(&stop.load)),
+                ScheduleAction::CycleLoad,
+            ),
+            (String::from("Remove"), ScheduleAction::Remove),
+        ] {
+            commands.spawn(schedule_button(list, label, index, action));
+        }
+    }
+
+    commands.spawn(schedule_button(
+        list,
+        String::from("Add stop"),
+        schedule.stops.len(),
+        ScheduleAction::Add,
+    ));
+}
+
+fn schedule_button(
+    list: Entity,
+    label: String,
+    stop: usize,
+    action: ScheduleAction,
+) -> impl Bundle {
+    (
+        Text::new(label),
+        ScheduleButton { stop, action },
+        ChildOf(list),
+        observe(on_schedule_button_click),
+    )
+}
+
+fn on_schedule_button_click(
+    click: On<Pointer<Click>>,
+    inspected: Res<Inspected>,
+    buttons: Query<&ScheduleButton>,
+    mut schedules: Query<&mut CartSchedule>,
+    stations: Query<Entity, With<Station>>,
+    item_defs: Res<Assets<ItemDef>>,
+    asset_server: Res<AssetServer>,
+) {
+    let Ok(ScheduleButton { stop, action }) = buttons.get(click.entity) else {
+        return;
+    };
+
+    let Ok(mut schedule) = schedules.get_mut(inspected.0) else {
+        return;
+    };
+
+    let mut stations: Vec<Entity> = stations.iter().collect();
+    stations.sort();
+
+    match action {
+        ScheduleAction::Add => {
+            if let Some(station) = stations.first() {
+                schedule.stops.push(CartStop {
+                    station: *station,
+                    unload: CargoRule::Nothing,
+                    load: CargoRule::Nothing,
+                });
+            }
+        }
+        ScheduleAction::Remove => {
+            if *stop < schedule.stops.len() {
+                schedule.stops.remove(*stop);
+            }
+        }
+        ScheduleAction::CycleStation => {
+            let Some(cart_stop) = schedule.stops.get_mut(*stop) else {
+                return;
+            };
+
+            let index = stations.iter().position(|s| *s == cart_stop.station);
+
+            if let Some(station) = index.map_or(stations.first(), |index| {
+                stations.get((index + 1) % stations.len())
+            }) {
+                cart_stop.station = *station;
+            }
+        }
+        ScheduleAction::CycleUnload | ScheduleAction::CycleLoad => {
+            let Some(cart_stop) = schedule.stops.get_mut(*stop) else {
+                return;
+            };
+
+            let rule = match action {
+                ScheduleAction::CycleUnload => &mut cart_stop.unload,
+                _ => &mut cart_stop.load,
+            };
+
+            let rules = cargo_rule_options(&item_defs, &asset_server);
+            let next = rules
+                .iter()
+                .position(|(option, _)| option == rule)
+                .map_or(0, |index| (index + 1) % rules.len());
+
+            *rule = rules[next].0.clone();
+        }
+    }
+}
+
+fn spawn_cart_contents(
+    mut commands: Commands,
+    right_page: Single<Entity, With<UITomeRightPageRoot>>,
+    inspected: Res<Inspected>,
+    inventory: Query<&Inventory>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            DespawnOnExit(InspectTabs::Schedule),
+            ChildOf(*right_page),
+        ))
+        .id();
+
+    for slot in inventory.iter_descendants(inspected.0) {
+        commands.spawn((widgets::item_plate(slot), ChildOf(id)));
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::{
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+    ui_widgets::observe,
+};
+
+use crate::{
+    gameplay::{
+        cart::{BuyCart, assets::CartDef},
+        structure::station::Station,
+        tome::{
+            UITomeLeftPageRoot,
+            inspect::{InspectTabs, Inspected},
+            list_page,
+        },
+    },
+    input::input_map::TextEntryFocus,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(InspectTabs::Station), spawn_station_page);
+
+    app.add_systems(OnExit(InspectTabs::Station), release_text_entry);
+
+    app.add_systems(
+        Update,
+        (
+            type_station_name.run_if(resource_equals(TextEntryFocus(true))),
+            refresh_station_name,
+        )
+            .chain()
+            .run_if(in_state(InspectTabs::Station)),
+    );
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct StationNameField;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BuyCartButton(Handle<CartDef>);
+
+fn spawn_station_page(
+    mut commands: Commands,
+    left_page: Single<Entity, With<UITomeLeftPageRoot>>,
+    inspected: Res<Inspected>,
+    stations: Query<(), With<Station>>,
+    cart_defs: Res<Assets<CartDef>>,
+    asset_server: Res<AssetServer>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            DespawnOnExit(InspectTabs::Station),
+            ChildOf(*left_page),
+        ))
+        .id();
+
+    if !stations.contains(inspected.0) {
+        commands.spawn((Text::new("Not a station"), ChildOf(id)));
+        return;
+    }
+
+    commands.spawn((
+        Text::default(),
+        StationNameField,
+        ChildOf(id),
+        observe(
+            |_click: On<Pointer<Click>>, mut focus: ResMut<TextEntryFocus>| {
+                focus.0 = !focus.0;
+            },
+        ),
+    ));
+
+    for (asset_id, cart_def) in cart_defs.iter() {
+        let Some(handle) = asset_server.get_id_handle(asset_id) else {
+            continue;
+        };
+
+        commands.spawn((
+            Text::new(format!("Buy {}", cart_def.name)),
+            BuyCartButton(handle),
+            ChildOf(id),
+            observe(on_buy_cart_click),
+        ));
+    }
+}
+
+fn on_buy_cart_click(
+    click: On<Pointer<Click>>,
+    inspected: Res<Inspected>,
+    buttons: Query<&BuyCartButton>,
+    mut commands: Commands,
+) {
+    let Ok(BuyCartButton(cart)) = buttons.get(click.entity) else {
+        return;
+    };
+
+    commands.trigger(BuyCart {
+        entity: inspected.0,
+        cart: cart.clone(),
+    });
+}
+
+fn type_station_name(
+    mut keyboard_input: MessageReader<KeyboardInput>,
+    inspected: Res<Inspected>,
+    mut stations: Query<&mut Station>,
+    mut focus: ResMut<TextEntryFocus>,
+) {
+    let Ok(mut station) = stations.get_mut(inspected.0) else {
+        return;
+    };
+
+    for event in keyboard_input.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(text) => station.name.push_str(text),
+            Key::Space => station.name.push(' '),
+            Key::Backspace => {
+                station.name.pop();
+            }
+            Key::Enter | Key::Escape => focus.0 = false,
+            _ => {}
+        }
+    }
+}
+
+fn refresh_station_name(
+    inspected: Res<Inspected>,
+    stations: Query<&Station>,
+    focus: Res<TextEntryFocus>,
+    name_fields: Query<&mut Text, With<StationNameField>>,
+) {
+    let Ok(station) = stations.get(inspected.0) else {
+        return;
+    };
+
+    let cursor = if focus.0 { "_" } else { "" };
+
+    for mut text in name_fields {
+        text.0 = format!("Name: {}{cursor}", station.name);
+    }
+}
+
+fn release_text_entry(mut focus: ResMut<TextEntryFocus>) {
+    focus.0 = false;
+}
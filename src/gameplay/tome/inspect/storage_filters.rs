@@ -0,0 +1,141 @@
+use bevy::{prelude::*, ui_widgets::observe};
+
+use crate::{
+    gameplay::{
+        inventory::prelude::*,
+        structure::storage::{Storage, StorageSlot},
+        tome::{
+            UITomeLeftPageRoot, UITomeRightPageRoot,
+            inspect::{InspectTabs, Inspected},
+            list_page,
+        },
+    },
+    widgets,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(InspectTabs::StorageFilters),
+        (spawn_filter_list, spawn_storage_contents),
+    );
+
+    app.add_systems(
+        Update,
+        refresh_filter_toggles.run_if(in_state(InspectTabs::StorageFilters)),
+    );
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct FilterToggle {
+    filter: ItemFilter,
+    label: String,
+}
+
+fn spawn_filter_list(
+    mut commands: Commands,
+    left_page: Single<Entity, With<UITomeLeftPageRoot>>,
+    inspected: Res<Inspected>,
+    storages: Query<(), With<Storage>>,
+    item_defs: Res<Assets<ItemDef>>,
+    asset_server: Res<AssetServer>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            DespawnOnExit(InspectTabs::StorageFilters),
+            ChildOf(*left_page),
+        ))
+        .id();
+
+    if !storages.contains(inspected.0) {
+        commands.spawn((Text::new("Nothing can be stored here"), ChildOf(id)));
+        return;
+    }
+
+    let mut toggles: Vec<(ItemFilter, String)> = Vec::new();
+
+    for (asset_id, item_def) in item_defs.iter() {
+        if let Some(handle) = asset_server.get_id_handle(asset_id) {
+            toggles.push((ItemFilter::Item(handle), item_def.name.clone()));
+        }
+    }
+
+    for tag in ItemTag::ALL {
+        toggles.push((ItemFilter::Tag(tag), format!("Tag: {tag:?}")));
+    }
+
+    for taxonomy in Taxonomy::ALL {
+        toggles.push((
+            ItemFilter::Taxonomy(taxonomy.clone()),
+            format!("Taxonomy: {taxonomy:?}"),
+        ));
+    }
+
+    for (filter, label) in toggles {
+        commands.spawn((
+            Text::default(),
+            FilterToggle { filter, label },
+            ChildOf(id),
+            observe(on_filter_toggle),
+        ));
+    }
+}
+
+fn on_filter_toggle(
+    click: On<Pointer<Click>>,
+    inspected: Res<Inspected>,
+    toggles: Query<&FilterToggle>,
+    mut storages: Query<&mut Storage>,
+) {
+    let Ok(toggle) = toggles.get(click.entity) else {
+        return;
+    };
+
+    if let Ok(mut storage) = storages.get_mut(inspected.0) {
+        storage.toggle_filter(toggle.filter.clone());
+    }
+}
+
+fn refresh_filter_toggles(
+    inspected: Res<Inspected>,
+    storages: Query<&Storage>,
+    toggles: Query<(&FilterToggle, &mut Text)>,
+) {
+    let Ok(storage) = storages.get(inspected.0) else {
+        return;
+    };
+
+    for (toggle, mut text) in toggles {
+        let marker = if storage.filters.contains(&toggle.filter) {
+            "[x]"
+        } else {
+            "[ ]"
+        };
+
+        text.0 = format!("{marker} {}", toggle.label);
+    }
+}
+
+fn spawn_storage_contents(
+    mut commands: Commands,
+    right_page: Single<Entity, With<UITomeRightPageRoot>>,
+    inspected: Res<Inspected>,
+    inventory: Query<&Inventory>,
+    storage_slots: Query<(), With<StorageSlot>>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            DespawnOnExit(InspectTabs::StorageFilters),
+            ChildOf(*right_page),
+        ))
+        .id();
+
+    for slot in inventory
+        .iter_descendants(inspected.0)
+        .filter(|slot| storage_slots.contains(*slot))
+    {
+        commands.spawn((widgets::item_plate(slot), ChildOf(id)));
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::{prelude::*, ui_widgets::observe};
+
+use crate::gameplay::{
+    blueprint::{
+        BlueprintClipboard, BlueprintTool,
+        storage::{SaveBlueprint, SavedBlueprints},
+    },
+    tome::{
+        TomeMenu, UITomeLeftPageRoot, UITomeRightPageRoot, inventory::InventoryTabs, list_page,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(InventoryTabs::Blueprints),
+        (spawn_blueprint_list, spawn_clipboard_page),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            (despawn_blueprint_list, spawn_blueprint_list)
+                .chain()
+                .run_if(resource_changed::<SavedBlueprints>),
+            (despawn_clipboard_page, spawn_clipboard_page)
+                .chain()
+                .run_if(resource_changed::<BlueprintClipboard>),
+        )
+            .run_if(in_state(InventoryTabs::Blueprints)),
+    );
+}
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BlueprintList;
+
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct ClipboardPage;
+
+/// Index into the saved blueprints
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+struct BlueprintButton(usize);
+
+fn spawn_blueprint_list(
+    mut commands: Commands,
+    left_page: Single<Entity, With<UITomeLeftPageRoot>>,
+    saved_blueprints: Res<SavedBlueprints>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            BlueprintList,
+            ChildOf(*left_page),
+            DespawnOnExit(InventoryTabs::Blueprints),
+        ))
+        .id();
+
+    if saved_blueprints.0.is_empty() {
+        commands.spawn((Text::new("No saved blueprints"), ChildOf(id)));
+    }
+
+    for (index, blueprint) in saved_blueprints.0.iter().enumerate() {
+        commands.spawn((
+            Text::new(format!(
+                "{} ({} constructs)",
+                blueprint.name,
+                blueprint.entries.len()
+            )),
+            BlueprintButton(index),
+            ChildOf(id),
+            observe(on_blueprint_click),
+        ));
+    }
+}
+
+fn despawn_blueprint_list(mut commands: Commands, lists: Query<Entity, With<BlueprintList>>) {
+    for list in lists {
+        commands.entity(list).despawn();
+    }
+}
+
+fn spawn_clipboard_page(
+    mut commands: Commands,
+    right_page: Single<Entity, With<UITomeRightPageRoot>>,
+    clipboard: Res<BlueprintClipboard>,
+    saved_blueprints: Res<SavedBlueprints>,
+) {
+    let id = commands
+        .spawn((
+            list_page(),
+            ClipboardPage,
+            ChildOf(*right_page),
+            DespawnOnExit(InventoryTabs::Blueprints),
+        ))
+        .id();
+
+    let Some(blueprint) = &clipboard.0 else {
+        commands.spawn((Text::new("Clipboard is empty"), ChildOf(id)));
+        return;
+    };
+
+    commands.spawn((
+        Text::new(format!("Clipboard: {} constructs", blueprint.entries.len())),
+        ChildOf(id),
+    ));
+
+    let name = format!("Blueprint {}", saved_blueprints.0.len() + 1);
+
+    commands.spawn((
+        Text::new(format!("Save as {name}")),
+        ChildOf(id),
+        observe(move |_click: On<Pointer<Click>>, mut commands: Commands| {
+            commands.trigger(SaveBlueprint(name.clone()));
+        }),
+    ));
+}
+
+fn despawn_clipboard_page(mut commands: Commands, pages: Query<Entity, With<ClipboardPage>>) {
+    for page in pages {
+        commands.entity(page).despawn();
+    }
+}
+
+/// Copies the blueprint into the clipboard and closes the tome to paste it
+fn on_blueprint_click(
+    click: On<Pointer<Click>>,
+    buttons: Query<&BlueprintButton>,
+    saved_blueprints: Res<SavedBlueprints>,
+    mut clipboard: ResMut<BlueprintClipboard>,
+    mut tool: ResMut<BlueprintTool>,
+    mut next_tome_menu: ResMut<NextState<TomeMenu>>,
+) {
+    let Some(blueprint) = buttons
+        .get(click.entity)
+        .ok()
+        .and_then(|button| saved_blueprints.0.get(button.0))
+    else {
+        return;
+    };
+
+    clipboard.0 = Some(blueprint.clone());
+    *tool = BlueprintTool::Pasting;
+    next_tome_menu.set(TomeMenu::None);
+}
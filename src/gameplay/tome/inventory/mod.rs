@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use crate::gameplay::tome::{TomeMenu, tome_plugin::TomePlugin};
 
+pub mod blueprints;
 pub mod items;
 pub mod people;
 pub mod recipes;
@@ -13,10 +14,16 @@ pub(super) fn plugin(app: &mut App) {
             ("Items", InventoryTabs::Items),
             ("People", InventoryTabs::People),
             ("Recipes", InventoryTabs::Recipes),
+            ("Blueprints", InventoryTabs::Blueprints),
         ],
     });
 
-    app.add_plugins((items::plugin, people::plugin, recipes::plugin));
+    app.add_plugins((
+        blueprints::plugin,
+        items::plugin,
+        people::plugin,
+        recipes::plugin,
+    ));
 }
 
 #[derive(SubStates, Component, Reflect, Debug, Hash, PartialEq, Eq, Clone, Copy, Default)]
@@ -27,4 +34,5 @@ pub enum InventoryTabs {
     Items,
     People,
     Recipes,
+    Blueprints,
 }
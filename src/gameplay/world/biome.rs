@@ -0,0 +1,129 @@
+use bevy::{asset::LoadedFolder, ecs::system::SystemParam, prelude::*};
+use serde::Deserialize;
+
+use crate::{
+    assets::{
+        indexing::{AssetIndexPlugin, IndexMap, Indexable},
+        loaders::toml::{FromToml, TomlAssetPlugin},
+        tracking::LoadResource,
+    },
+    gameplay::world::{
+        generation::{Terrain, WorldGen},
+        tilemap::coord::tile_hash,
+    },
+};
+
+/// Colour multiplied into the ground of river tiles, drawing them as water
+const RIVER_TINT: Color = Color::srgb(0.3, 0.5, 1.0);
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        TomlAssetPlugin::<BiomeDef>::extensions(&["biome.toml"]),
+        AssetIndexPlugin::<BiomeDef>::default(),
+    ));
+
+    app.load_resource::<BiomeAssets>();
+}
+
+#[derive(Asset, Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct BiomeAssets {
+    pub manifest_folder: Handle<LoadedFolder>,
+}
+
+impl FromWorld for BiomeAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        Self {
+            manifest_folder: asset_server.load_folder("manifests/biomes"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct BiomeRaw {
+    pub id: String,
+    pub name: String,
+    pub tileset: String,
+    pub texture_indices: Vec<u32>,
+    /// Temperature and moisture, each between -1 and 1, the biome is most typical of
+    pub climate: [f32; 2],
+    /// Colour multiplied into the tileset, in sRGB. Tells biomes sharing a tileset apart.
+    pub tint: Option<[f32; 3]>,
+}
+
+#[derive(Asset, Reflect, Debug)]
+pub struct BiomeDef {
+    pub id: String,
+    pub name: String,
+    pub tileset: Handle<Image>,
+    /// Tiles of the tileset picked from for the biome's ground, at random per tile
+    pub texture_indices: Vec<u32>,
+    pub climate: Vec2,
+    pub tint: Color,
+}
+
+impl FromToml for BiomeDef {
+    type Raw = BiomeRaw;
+
+    fn from_toml(raw: Self::Raw, load_context: &mut bevy::asset::LoadContext) -> Self {
+        Self {
+            id: raw.id,
+            name: raw.name,
+            tileset: load_context.load(raw.tileset),
+            texture_indices: raw.texture_indices,
+            climate: Vec2::from(raw.climate),
+            tint: raw
+                .tint
+                .map(|[r, g, b]| Color::srgb(r, g, b))
+                .unwrap_or(Color::WHITE),
+        }
+    }
+}
+
+impl Indexable for BiomeDef {
+    fn index(&self) -> &String {
+        &self.id
+    }
+}
+
+impl BiomeDef {
+    /// Tile of the tileset covering the coordinate, varied between tiles but stable for each
+    pub fn texture_index(&self, coord: IVec2) -> u32 {
+        if self.texture_indices.is_empty() {
+            return 0;
+        }
+
+        self.texture_indices[tile_hash(coord, 0) as usize % self.texture_indices.len()]
+    }
+
+    /// Colour the biome's ground is drawn with on terrain of the given kind
+    pub fn ground_tint(&self, terrain: Terrain) -> Color {
+        match terrain {
+            Terrain::River => RIVER_TINT,
+            Terrain::Land | Terrain::Riverbank => self.tint,
+        }
+    }
+}
+
+/// Lookup of the biome covering a tile
+#[derive(SystemParam)]
+pub struct Biomes<'w> {
+    world_gen: Res<'w, WorldGen>,
+    index: Res<'w, IndexMap<BiomeDef>>,
+    defs: Res<'w, Assets<BiomeDef>>,
+}
+
+impl Biomes<'_> {
+    pub fn get(&self, coord: IVec2) -> Option<&BiomeDef> {
+        let biome = self.world_gen.biome_map.biome_at(coord)?;
+        self.by_index(biome)
+    }
+
+    /// Biome at the index of the world spec, as found in chunk plans
+    pub fn by_index(&self, biome: usize) -> Option<&BiomeDef> {
+        let id = &self.world_gen.spec.biomes.get(biome)?.id;
+        self.index.get(id).and_then(|id| self.defs.get(*id))
+    }
+}
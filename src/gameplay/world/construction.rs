@@ -1,21 +1,38 @@
 use std::collections::HashMap;
 
-use bevy::{prelude::*, sprite::Anchor};
+use bevy::{ecs::system::SystemParam, prelude::*, sprite::Anchor};
 use bevy_aseprite_ultra::prelude::*;
 
 use crate::{
     gameplay::{
         FactorySystems,
+        blueprint::paste::BlueprintPreview,
         hud::hotbar::{HotbarActionKind, HotbarSelection, HotbarSelectionChanged},
-        inventory::prelude::{Inventory, ItemStack, can_afford, spend},
+        inventory::prelude::{
+            Inventory, ItemDef, ItemStack, can_afford, missing_cost, refund, spend,
+        },
         player::Player,
         sprite_sort::{YSortSprite, ZIndexSprite},
         structure::{
-            Structure, assets::StructureDef, foragers_outpost::ForagersOutpost,
-            interactable::Interactable, range::Range,
+            Structure,
+            adjacent_transfer::AdjacentTransfer,
+            assets::StructureDef,
+            deposit::Deposit,
+            foragers_outpost::ForagersOutpost,
+            interactable::Interactable,
+            orientation::{Faces, Orientation, PlacementOrientation},
+            path::{PathDef, PathSegment},
+            placement_rule::{PlacementFailure, TileContent},
+            range::Range,
         },
         world::{
+            biome::Biomes,
+            construction_site::construction_site,
             demolition::Demolished,
+            drag_placement::DragPlacement,
+            fog::{FogOfWar, Sight},
+            generation::{Terrain, WorldGen},
+            history::{History, HistoryStep, PlacedConstruct},
             tilemap::{
                 TileClicked,
                 coord::{Coord, translation_to_coord},
@@ -23,12 +40,14 @@ use crate::{
         },
     },
     input::cursor::CursorPosition,
+    widgets::tooltip::{HideTooltip, ShowTooltip, TooltipSource},
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<Constructions>();
     app.init_resource::<ValidPlacement>();
     app.init_resource::<Affordable>();
+    app.init_resource::<PlacementReport>();
 
     app.add_message::<StructureConstructed>();
 
@@ -52,6 +71,11 @@ pub(super) fn plugin(app: &mut App) {
             .run_if(on_message::<TileClicked>),
     );
 
+    app.add_systems(
+        Update,
+        explain_placement.run_if(resource_changed::<PlacementReport>),
+    );
+
     app.add_systems(
         FixedUpdate,
         remove_demolished_constructions.after(FactorySystems::Demolish),
@@ -61,6 +85,25 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Message, Reflect, Debug)]
 pub struct StructureConstructed(pub Entity);
 
+/// A construct taking the place of another on the same tile, such as a junction built on a path
+#[derive(Event, Reflect, Debug)]
+pub struct ConstructReplaced {
+    pub replaced: Entity,
+    pub replacement: Entity,
+}
+
+/// The kinds of constructs a player can place and demolish
+#[derive(Reflect, Debug, Clone)]
+pub enum ConstructKind {
+    Structure(Handle<StructureDef>),
+    Path(Handle<PathDef>),
+    /// Unfinished structure, along with the materials delivered to it so far
+    Site {
+        structure: Handle<StructureDef>,
+        delivered: HashMap<Handle<ItemDef>, u32>,
+    },
+}
+
 #[derive(Component, Reflect, Debug, Default)]
 #[reflect(Component)]
 pub struct ConstructionPreview;
@@ -73,6 +116,15 @@ pub struct ValidPlacement(pub bool);
 #[reflect(Resource)]
 pub struct Affordable(pub bool);
 
+/// Why the construction preview can't be placed where it is, explained in a tooltip
+#[derive(Resource, Reflect, Debug, Default, PartialEq)]
+#[reflect(Resource)]
+pub struct PlacementReport {
+    pub failures: Vec<PlacementFailure>,
+    /// Items the player is short of for the cost
+    pub missing: HashMap<Handle<ItemDef>, u32>,
+}
+
 #[derive(Resource, Reflect, Debug, Default, Deref, DerefMut)]
 #[reflect(Resource)]
 pub struct Constructions(pub HashMap<IVec2, Entity>);
@@ -82,86 +134,325 @@ fn spawn_preview(
     asset_server: Res<AssetServer>,
     hotbar_selection: HotbarSelection,
     structure_defs: Res<Assets<StructureDef>>,
+    path_defs: Res<Assets<PathDef>>,
 ) {
     let Some(action) = hotbar_selection.action() else {
         return;
     };
 
-    let id = commands
-        .spawn((
-            Name::new("Construction Preview"),
-            ConstructionPreview,
-            Coord(IVec2::ZERO),
-            Sprite {
-                color: Color::WHITE.with_alpha(0.5),
-                ..default()
-            },
-            YSortSprite,
-            ZIndexSprite(10),
-        ))
-        .id();
+    let preview = (
+        Name::new("Construction Preview"),
+        ConstructionPreview,
+        Coord(IVec2::ZERO),
+        Sprite {
+            color: Color::WHITE.with_alpha(0.5),
+            ..default()
+        },
+        YSortSprite,
+        ZIndexSprite(10),
+    );
 
     match action {
         HotbarActionKind::PlaceStructure(handle) => {
-            let sprite_path = format!(
-                "sprites/structures/{}.aseprite",
-                structure_defs.get(handle).unwrap().id.to_owned()
-            );
+            let Some(structure_def) = structure_defs.get(handle) else {
+                return;
+            };
 
-            commands.entity(id).insert((
+            commands.spawn((
+                preview,
+                structure_def.footprint.clone(),
                 Anchor(Vec2::new(0.0, -0.33)),
                 AseAnimation {
-                    aseprite: asset_server.load(sprite_path),
+                    aseprite: asset_server.load(&structure_def.sprite),
                     animation: Animation::tag("work"),
                 },
             ));
         }
-        HotbarActionKind::PlacePath => {
-            commands.entity(id).insert(AseSlice {
-                aseprite: asset_server.load("sprites/logistics/path_segments.aseprite"),
-                name: "C".into(),
-            });
+        HotbarActionKind::PlacePath(handle) => {
+            let Some(path_def) = path_defs.get(handle) else {
+                return;
+            };
+
+            commands.spawn((
+                preview,
+                AseSlice {
+                    aseprite: asset_server.load(&path_def.sprite),
+                    name: "C".into(),
+                },
+            ));
         }
-    };
+    }
 }
 
-fn despawn_preview(mut commands: Commands, previews: Query<Entity, With<ConstructionPreview>>) {
+fn despawn_preview(
+    mut commands: Commands,
+    previews: Query<Entity, With<ConstructionPreview>>,
+    mut placement_report: ResMut<PlacementReport>,
+) {
     for preview in previews {
         commands.entity(preview).despawn();
     }
+
+    placement_report.set_if_neq(PlacementReport::default());
 }
 
 fn calculate_valid_placement(
-    preview: Single<&Coord, With<ConstructionPreview>>,
+    preview: Single<&Coord, (With<ConstructionPreview>, Without<BlueprintPreview>)>,
+    hotbar_selection: HotbarSelection,
+    structure_defs: Res<Assets<StructureDef>>,
     constructions: Res<Constructions>,
+    path_segments: Query<&PathSegment>,
+    surroundings: PlacementSurroundings,
+    placement_orientation: Res<PlacementOrientation>,
     mut valid_placement: ResMut<ValidPlacement>,
+    mut placement_report: ResMut<PlacementReport>,
 ) {
-    let spot_occupied = constructions.contains_key(*preview);
-    valid_placement.0 = !spot_occupied;
+    let Some(action) = hotbar_selection.action() else {
+        return;
+    };
+
+    let failures = placement_failures(
+        action,
+        &preview.0,
+        &placement_orientation.0,
+        &structure_defs,
+        &constructions,
+        &path_segments,
+        &surroundings,
+    );
+
+    valid_placement.0 = failures.is_empty();
+
+    if placement_report.failures != failures {
+        placement_report.failures = failures;
+    }
+}
+
+/// What placement rules look at around a construct, besides the constructs themselves
+#[derive(SystemParam)]
+pub struct PlacementSurroundings<'w, 's> {
+    deposits: Query<'w, 's, (), With<Deposit>>,
+    biomes: Biomes<'w>,
+    fog: Res<'w, FogOfWar>,
+    world_gen: Res<'w, WorldGen>,
+    path_defs: Res<'w, Assets<PathDef>>,
+}
+
+impl PlacementSurroundings<'_, '_> {
+    pub fn is_revealed(&self, coord: IVec2) -> bool {
+        self.fog.is_revealed(coord)
+    }
+
+    pub fn is_river(&self, coord: IVec2) -> bool {
+        self.world_gen.terrain(coord) == Terrain::River
+    }
+
+    /// What occupies the tile, as far as placement rules are concerned
+    pub fn content(
+        &self,
+        tile: IVec2,
+        constructions: &Constructions,
+        path_segments: &Query<&PathSegment>,
+    ) -> TileContent {
+        match constructions.get(&tile) {
+            None => TileContent::Empty,
+            Some(entity) if self.deposits.contains(*entity) => TileContent::Deposit,
+            Some(entity) if path_segments.contains(*entity) => TileContent::Path,
+            Some(_) => TileContent::Other,
+        }
+    }
+
+    /// Id of the biome the tile lies in
+    pub fn biome(&self, tile: IVec2) -> Option<&str> {
+        self.biomes.get(tile).map(|biome| biome.id.as_str())
+    }
+}
+
+/// Whether the hotbar action may place its construct at the given coordinate
+pub fn placement_allowed(
+    action: &HotbarActionKind,
+    coord: &IVec2,
+    orientation: &Orientation,
+    structure_defs: &Assets<StructureDef>,
+    constructions: &Constructions,
+    path_segments: &Query<&PathSegment>,
+    surroundings: &PlacementSurroundings,
+) -> bool {
+    placement_failures(
+        action,
+        coord,
+        orientation,
+        structure_defs,
+        constructions,
+        path_segments,
+        surroundings,
+    )
+    .is_empty()
+}
+
+/// Every reason the hotbar action can't place its construct at the given coordinate. Junctions go
+/// on top of path segments, everything else needs every tile of its footprint empty, only bridges
+/// go over rivers, and structures also need each placement rule of their manifest to hold.
+pub fn placement_failures(
+    action: &HotbarActionKind,
+    coord: &IVec2,
+    orientation: &Orientation,
+    structure_defs: &Assets<StructureDef>,
+    constructions: &Constructions,
+    path_segments: &Query<&PathSegment>,
+    surroundings: &PlacementSurroundings,
+) -> Vec<PlacementFailure> {
+    let structure_def = match action {
+        HotbarActionKind::PlaceStructure(handle) => structure_defs.get(handle),
+        HotbarActionKind::PlacePath(_) => None,
+    };
+
+    let Some(structure_def) = structure_def else {
+        let mut failures = vec![];
+
+        if constructions.contains_key(coord) {
+            failures.push(PlacementFailure::Occupied);
+        }
+
+        if !surroundings.fog.is_revealed(*coord) {
+            failures.push(PlacementFailure::Unexplored);
+        }
+
+        let bridge = match action {
+            HotbarActionKind::PlacePath(handle) => surroundings
+                .path_defs
+                .get(handle)
+                .is_some_and(|path_def| path_def.bridge),
+            HotbarActionKind::PlaceStructure(_) => false,
+        };
+
+        if !bridge && surroundings.is_river(*coord) {
+            failures.push(PlacementFailure::Water);
+        }
+
+        return failures;
+    };
+
+    let mut failures = vec![];
+    let footprint = orientation.footprint(&structure_def.footprint);
+
+    if !footprint
+        .tiles(*coord)
+        .all(|tile| surroundings.fog.is_revealed(tile))
+    {
+        failures.push(PlacementFailure::Unexplored);
+    }
+
+    if footprint
+        .tiles(*coord)
+        .any(|tile| surroundings.is_river(tile))
+    {
+        failures.push(PlacementFailure::Water);
+    }
+
+    if structure_def.junction.is_some() {
+        if !constructions
+            .get(coord)
+            .is_some_and(|entity| path_segments.contains(*entity))
+        {
+            failures.push(PlacementFailure::NotOnPath);
+        }
+    } else if footprint
+        .tiles(*coord)
+        .any(|tile| constructions.contains_key(&tile))
+    {
+        failures.push(PlacementFailure::Occupied);
+    }
+
+    let content = |tile: IVec2| surroundings.content(tile, constructions, path_segments);
+
+    for rule in structure_def.placement_rules.iter() {
+        if !rule.satisfied(*coord, &footprint, content, |tile| surroundings.biome(tile)) {
+            failures.push(PlacementFailure::Rule(rule.clone()));
+        }
+    }
+
+    failures
 }
 
 fn calculate_affordability(
     hotbar_selection: HotbarSelection,
     structure_defs: Res<Assets<StructureDef>>,
+    path_defs: Res<Assets<PathDef>>,
     mut affordable: ResMut<Affordable>,
+    mut placement_report: ResMut<PlacementReport>,
     player: Single<Entity, With<Player>>,
     inventory: Query<&Inventory>,
     stacks: Query<&mut ItemStack>,
 ) {
-    let Some(HotbarActionKind::PlaceStructure(handle)) = hotbar_selection.action() else {
-        return;
-    };
+    let cost = hotbar_selection
+        .action()
+        .and_then(|action| placement_cost(action, &structure_defs, &path_defs));
 
-    let Some(structure) = structure_defs.get(handle) else {
+    let missing = cost
+        .map(|cost| missing_cost(*player, cost, &inventory, &stacks))
+        .unwrap_or_default();
+
+    affordable.0 = missing.is_empty();
+
+    if placement_report.missing != missing {
+        placement_report.missing = missing;
+    }
+}
+
+/// Lists every failed placement rule and missing cost item next to the preview. Runs placed by
+/// dragging show their own tooltip instead.
+fn explain_placement(
+    placement_report: Res<PlacementReport>,
+    drag_placement: Res<DragPlacement>,
+    item_defs: Res<Assets<ItemDef>>,
+    mut commands: Commands,
+) {
+    if drag_placement.is_dragging() {
         return;
-    };
+    }
 
-    affordable.0 = can_afford(*player, &structure.cost, &inventory, &stacks);
+    let mut lines: Vec<String> = placement_report
+        .failures
+        .iter()
+        .map(PlacementFailure::describe)
+        .collect();
+
+    for (item, quantity) in placement_report.missing.iter() {
+        if let Some(item_def) = item_defs.get(item) {
+            lines.push(format!("Missing {quantity} {}", item_def.name));
+        }
+    }
+
+    if lines.is_empty() {
+        commands.trigger(HideTooltip(TooltipSource::Placement));
+    } else {
+        commands.trigger(ShowTooltip {
+            source: TooltipSource::Placement,
+            text: lines.join("\n"),
+        });
+    }
+}
+
+/// Cost the player pays up front for placing a single construct with the given hotbar action.
+/// Construction sites have none, as porters deliver their materials instead.
+pub fn placement_cost<'a>(
+    action: &HotbarActionKind,
+    structure_defs: &'a Assets<StructureDef>,
+    path_defs: &'a Assets<PathDef>,
+) -> Option<&'a HashMap<Handle<ItemDef>, u32>> {
+    match action {
+        HotbarActionKind::PlaceStructure(handle) => structure_defs
+            .get(handle)
+            .filter(|structure| structure.build_time.is_none())
+            .map(|structure| &structure.cost),
+        HotbarActionKind::PlacePath(handle) => path_defs.get(handle).map(|path_def| &path_def.cost),
+    }
 }
 
 fn move_preview(
     cursor_position: Res<CursorPosition>,
-    mut preview_query: Query<&mut Coord, With<ConstructionPreview>>,
+    mut preview_query: Query<&mut Coord, (With<ConstructionPreview>, Without<BlueprintPreview>)>,
 ) {
     for mut coord in preview_query.iter_mut() {
         *coord = translation_to_coord(&cursor_position.0);
@@ -171,7 +462,7 @@ fn move_preview(
 fn color_preview(
     valid_placement: Res<ValidPlacement>,
     affordable: Res<Affordable>,
-    mut preview_query: Query<&mut Sprite, With<ConstructionPreview>>,
+    mut preview_query: Query<&mut Sprite, (With<ConstructionPreview>, Without<BlueprintPreview>)>,
 ) {
     for mut sprite in preview_query.iter_mut() {
         sprite.color = if !valid_placement.0 || !affordable.0 {
@@ -188,13 +479,18 @@ fn construct(
     asset_server: Res<AssetServer>,
     hotbar_selection: HotbarSelection,
     structure_defs: ResMut<Assets<StructureDef>>,
+    path_defs: Res<Assets<PathDef>>,
     mut constructions: ResMut<Constructions>,
+    path_segments: Query<&PathSegment>,
+    surroundings: PlacementSurroundings,
+    placement_orientation: Res<PlacementOrientation>,
     mut structures_constructed: MessageWriter<StructureConstructed>,
     player: Single<Entity, With<Player>>,
     inventory: Query<&Inventory>,
     mut stacks: Query<&mut ItemStack>,
+    mut history: ResMut<History>,
 ) {
-    let Some(HotbarActionKind::PlaceStructure(handle)) = hotbar_selection.action() else {
+    let Some(action @ HotbarActionKind::PlaceStructure(handle)) = hotbar_selection.action() else {
         return;
     };
 
@@ -202,48 +498,166 @@ fn construct(
         .get(handle)
         .expect("Attempted to spawn non-existent structure");
 
+    let orientation = placement_orientation.0;
+    let footprint = orientation.footprint(&structure.footprint);
+
+    // Everything placed at once, such as along a dragged run, is undone together
+    let mut placed = Vec::new();
+    let mut removed = Vec::new();
+
     for tile_click in tile_clicks.read() {
-        if !can_afford(*player, &structure.cost, &inventory, &stacks) {
+        if !placement_allowed(
+            action,
+            &tile_click.0.0,
+            &orientation,
+            &structure_defs,
+            &constructions,
+            &path_segments,
+            &surroundings,
+        ) {
             continue;
         }
 
-        spend(*player, &structure.cost, &inventory, &mut stacks);
+        // Construction sites are paid for by porters delivering the materials
+        let entity = if structure.build_time.is_some() {
+            placed.push(PlacedConstruct {
+                orientation,
+                ..PlacedConstruct::new(
+                    tile_click.0.0,
+                    ConstructKind::Site {
+                        structure: handle.clone(),
+                        delivered: HashMap::new(),
+                    },
+                )
+            });
 
-        let entity = commands
-            .spawn((
-                Name::new(structure.name.clone()),
-                Coord(IVec2::new(tile_click.0.x, tile_click.0.y)),
-                Anchor(Vec2::new(0.0, -0.33)),
-                Sprite::default(),
-                AseAnimation {
-                    aseprite: asset_server
-                        .load(format!("sprites/structures/{}.aseprite", structure.id)),
-                    animation: Animation::tag("work"),
-                },
-                YSortSprite,
-                ZIndexSprite(10),
-                Structure(handle.clone()),
-                Interactable,
-            ))
-            .id();
-
-        if structure.id == "foragers_outpost" {
             commands
-                .entity(entity)
-                .insert((ForagersOutpost, Range::Diamond(4)));
+                .spawn(construction_site(
+                    structure,
+                    handle.clone(),
+                    tile_click.0.0,
+                    orientation,
+                    None,
+                    &asset_server,
+                ))
+                .id()
+        } else {
+            if !can_afford(*player, &structure.cost, &inventory, &stacks) {
+                continue;
+            }
+
+            spend(*player, &structure.cost, &inventory, &mut stacks);
+
+            // Junctions replace the path segment they are placed on
+            let replaced = constructions
+                .get(&tile_click.0.0)
+                .copied()
+                .filter(|construction| path_segments.contains(*construction));
+
+            if let Some(replaced) = replaced
+                && let Ok(path_segment) = path_segments.get(replaced)
+            {
+                if let Some(path_def) = path_defs.get(&path_segment.0) {
+                    refund(*player, &path_def.cost, &inventory, &mut stacks);
+                }
+
+                commands.entity(replaced).despawn();
+
+                removed.push(PlacedConstruct::new(
+                    tile_click.0.0,
+                    ConstructKind::Path(path_segment.0.clone()),
+                ));
+            }
+
+            placed.push(PlacedConstruct {
+                orientation,
+                ..PlacedConstruct::new(tile_click.0.0, ConstructKind::Structure(handle.clone()))
+            });
+
+            let entity = spawn_structure(
+                &mut commands,
+                structure,
+                handle.clone(),
+                tile_click.0.0,
+                orientation,
+                &asset_server,
+            );
+
+            structures_constructed.write(StructureConstructed(entity));
+
+            if let Some(replaced) = replaced {
+                commands.trigger(ConstructReplaced {
+                    replaced,
+                    replacement: entity,
+                });
+            }
+
+            entity
+        };
+
+        for tile in footprint.tiles(tile_click.0.xy()) {
+            constructions.insert(tile, entity);
         }
+    }
+
+    if !placed.is_empty() {
+        history.record(HistoryStep::Constructs { placed, removed });
+    }
+}
+
+/// Spawns a finished structure at the given coordinate
+pub fn spawn_structure(
+    commands: &mut Commands,
+    structure: &StructureDef,
+    handle: Handle<StructureDef>,
+    coord: IVec2,
+    orientation: Orientation,
+    asset_server: &AssetServer,
+) -> Entity {
+    let entity = commands
+        .spawn((
+            Name::new(structure.name.clone()),
+            Coord(coord),
+            Anchor(Vec2::new(0.0, -0.33)),
+            Sprite {
+                flip_x: orientation.flips_sprite(),
+                ..default()
+            },
+            AseAnimation {
+                aseprite: asset_server.load(&structure.sprite),
+                animation: Animation::tag("work"),
+            },
+            YSortSprite,
+            ZIndexSprite(10),
+            Structure(handle),
+            orientation.footprint(&structure.footprint),
+            orientation,
+            Faces::new(structure, &orientation),
+            Interactable,
+            Sight(Range::Diamond(structure.sight)),
+        ))
+        .id();
 
-        constructions.insert(tile_click.0.xy(), entity);
+    if structure.id == "foragers_outpost" {
+        commands
+            .entity(entity)
+            .insert((ForagersOutpost, Range::Diamond(4)));
+    }
 
-        structures_constructed.write(StructureConstructed(entity));
+    if let Some(rate) = structure.adjacent_transfer_rate {
+        commands
+            .entity(entity)
+            .insert(AdjacentTransfer::per_second(rate));
     }
+
+    entity
 }
 
 fn remove_demolished_constructions(
     mut demolitions: MessageReader<Demolished>,
     mut constructions: ResMut<Constructions>,
 ) {
-    for Demolished { coord, .. } in demolitions.read() {
-        constructions.remove(&coord.xy());
+    for Demolished { entity, .. } in demolitions.read() {
+        constructions.retain(|_, construction| construction != entity);
     }
 }
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_aseprite_ultra::prelude::*;
+
+use crate::gameplay::{
+    FactorySystems,
+    inventory::prelude::*,
+    recipe::{
+        assets::Recipe,
+        progress::{ProgressBarFillOf, fill_progress_bar, progress_bar},
+        select::SelectRecipe,
+    },
+    sprite_sort::{YSortSprite, ZIndexSprite},
+    structure::{assets::StructureDef, orientation::Orientation},
+    world::{
+        construction::{Constructions, StructureConstructed, spawn_structure},
+        demolition::Demolishable,
+        tilemap::coord::Coord,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(on_site_add);
+
+    app.add_systems(
+        FixedUpdate,
+        (close_filled_site_slots, build_sites)
+            .chain()
+            .in_set(FactorySystems::Work),
+    );
+
+    app.add_systems(Update, update_site_progress_bars);
+}
+
+/// Placed structure waiting on porters to deliver its materials before it is built
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+#[require(Inventory, Demolishable)]
+pub struct ConstructionSite {
+    pub structure: Handle<StructureDef>,
+    pub orientation: Orientation,
+    /// Building time, ticking once every material has been delivered
+    pub timer: Timer,
+    /// Recipe selected once built, instead of the default one
+    pub recipe: Option<AssetId<Recipe>>,
+}
+
+/// Site slot taking deliveries of one material until the required quantity is reached
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct SiteSlot {
+    pub required: u32,
+}
+
+/// Materials a site starts out with, such as those it held before its demolition was undone
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct DeliveredMaterials(pub HashMap<Handle<ItemDef>, u32>);
+
+/// Site for the given structure, built once its cost has been delivered
+pub fn construction_site(
+    structure: &StructureDef,
+    handle: Handle<StructureDef>,
+    coord: IVec2,
+    orientation: Orientation,
+    recipe: Option<AssetId<Recipe>>,
+    asset_server: &AssetServer,
+) -> impl Bundle {
+    (
+        Name::new(format!("{} (site)", structure.name)),
+        ConstructionSite {
+            structure: handle,
+            orientation,
+            timer: Timer::from_seconds(structure.build_time.unwrap_or_default(), TimerMode::Once),
+            recipe,
+        },
+        Coord(coord),
+        orientation.footprint(&structure.footprint),
+        Anchor(Vec2::new(0.0, -0.33)),
+        Sprite {
+            color: Color::WHITE.with_alpha(0.5),
+            flip_x: orientation.flips_sprite(),
+            ..default()
+        },
+        AseAnimation {
+            aseprite: asset_server.load(&structure.sprite),
+            animation: Animation::tag("work"),
+        },
+        YSortSprite,
+        ZIndexSprite(10),
+    )
+}
+
+fn on_site_add(
+    add: On<Add, ConstructionSite>,
+    sites: Query<(&ConstructionSite, Option<&DeliveredMaterials>)>,
+    structure_defs: Res<Assets<StructureDef>>,
+    mut commands: Commands,
+) {
+    let Ok((site, delivered)) = sites.get(add.entity) else {
+        return;
+    };
+
+    let Some(structure_def) = structure_defs.get(&site.structure) else {
+        return;
+    };
+
+    for (item, required) in structure_def.cost.iter() {
+        let delivered = delivered
+            .and_then(|delivered| delivered.0.get(item))
+            .copied()
+            .unwrap_or_default();
+
+        commands.spawn((
+            item_stack_slot(add.entity, item.clone(), delivered),
+            SiteSlot {
+                required: *required,
+            },
+            DropOff::Item(item.clone()),
+        ));
+    }
+
+    commands.spawn(progress_bar(add.entity));
+}
+
+fn close_filled_site_slots(
+    slots: Query<(Entity, &SiteSlot, &ItemStack), With<DropOff>>,
+    mut commands: Commands,
+) {
+    for (slot, site_slot, stack) in slots {
+        if stack.quantity >= site_slot.required {
+            commands.entity(slot).remove::<DropOff>();
+        }
+    }
+}
+
+/// Builds sites with all of their materials delivered, replacing them with the finished structure
+fn build_sites(
+    sites: Query<(Entity, &mut ConstructionSite, &Coord)>,
+    inventory: Query<&Inventory>,
+    slots: Query<(&SiteSlot, &ItemStack)>,
+    structure_defs: Res<Assets<StructureDef>>,
+    mut constructions: ResMut<Constructions>,
+    mut structures_constructed: MessageWriter<StructureConstructed>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut site, coord) in sites {
+        let delivered = inventory.iter_descendants(entity).all(|slot| {
+            slots
+                .get(slot)
+                .is_ok_and(|(site_slot, stack)| stack.quantity >= site_slot.required)
+        });
+
+        if !delivered || !site.timer.tick(time.delta()).is_finished() {
+            continue;
+        }
+
+        let Some(structure_def) = structure_defs.get(&site.structure) else {
+            continue;
+        };
+
+        commands.entity(entity).despawn();
+
+        let structure = spawn_structure(
+            &mut commands,
+            structure_def,
+            site.structure.clone(),
+            coord.0,
+            site.orientation,
+            &asset_server,
+        );
+
+        for construction in constructions.values_mut() {
+            if *construction == entity {
+                *construction = structure;
+            }
+        }
+
+        if let Some(recipe) = site.recipe {
+            commands.trigger(SelectRecipe {
+                entity: structure,
+                recipe,
+            });
+        }
+
+        structures_constructed.write(StructureConstructed(structure));
+    }
+}
+
+fn update_site_progress_bars(
+    progress_bars: Query<(&mut Sprite, &ProgressBarFillOf)>,
+    sites: Query<&ConstructionSite>,
+) {
+    for (mut sprite, progress_bar_of) in progress_bars {
+        if let Ok(site) = sites.get(progress_bar_of.0) {
+            fill_progress_bar(&mut sprite, site.timer.fraction());
+        }
+    }
+}
+
+/// Materials delivered to a site so far, refunded when it is cancelled
+pub fn delivered_materials(
+    site: Entity,
+    inventory: &Query<&Inventory>,
+    slots: &Query<&ItemStack, With<SiteSlot>>,
+) -> HashMap<Handle<ItemDef>, u32> {
+    inventory
+        .iter_descendants(site)
+        .filter_map(|slot| slots.get(slot).ok())
+        .filter(|stack| stack.quantity > 0)
+        .map(|stack| (stack.item.clone(), stack.quantity))
+        .collect()
+}
@@ -7,8 +7,19 @@ use crate::{
         FactorySystems,
         inventory::prelude::{Inventory, ItemStack, refund},
         player::Player,
-        structure::{Structure, assets::StructureDef},
-        world::tilemap::coord::Coord,
+        recipe::select::SelectedRecipe,
+        structure::{
+            Structure,
+            assets::StructureDef,
+            orientation::Orientation,
+            path::{PathDef, PathSegment},
+        },
+        world::{
+            construction::ConstructKind,
+            construction_site::{ConstructionSite, SiteSlot, delivered_materials},
+            history::{History, HistoryStep, PlacedConstruct},
+            tilemap::coord::Coord,
+        },
     },
     input::input_map::{Action, InputActions, action_just_pressed},
 };
@@ -48,7 +59,7 @@ pub(super) fn plugin(app: &mut App) {
 #[derive(Message, Reflect, Debug)]
 pub struct Demolished {
     pub entity: Entity,
-    pub structure: Handle<StructureDef>,
+    pub construct: ConstructKind,
     pub coord: Coord,
 }
 
@@ -133,33 +144,82 @@ fn demolish_selection(
     mut selection: ResMut<DemolishSelection>,
     mut commands: Commands,
     mut demolitions: MessageWriter<Demolished>,
-    structures: Query<(&Structure, &Coord)>,
+    constructs: Query<(
+        &Coord,
+        Option<&Structure>,
+        Option<&PathSegment>,
+        Option<&ConstructionSite>,
+        Option<&Orientation>,
+        Option<&SelectedRecipe>,
+    )>,
+    inventory: Query<&Inventory>,
+    site_slots: Query<&ItemStack, With<SiteSlot>>,
+    mut history: ResMut<History>,
 ) {
+    let mut removed = Vec::new();
+
     for demolishable in selection.drain() {
         commands.entity(demolishable).despawn();
 
-        if let Ok((structure, coord)) = structures.get(demolishable) {
-            demolitions.write(Demolished {
-                entity: demolishable,
-                structure: structure.0.clone(),
-                coord: Coord(coord.0),
-            });
-        }
+        let Ok((coord, structure, path_segment, site, orientation, selected_recipe)) =
+            constructs.get(demolishable)
+        else {
+            continue;
+        };
+
+        let construct = match (structure, path_segment, site) {
+            (Some(structure), _, _) => ConstructKind::Structure(structure.0.clone()),
+            (None, Some(path_segment), _) => ConstructKind::Path(path_segment.0.clone()),
+            (None, None, Some(site)) => ConstructKind::Site {
+                structure: site.structure.clone(),
+                delivered: delivered_materials(demolishable, &inventory, &site_slots),
+            },
+            (None, None, None) => continue,
+        };
+
+        removed.push(PlacedConstruct {
+            coord: coord.0,
+            construct: construct.clone(),
+            orientation: orientation.copied().unwrap_or_default(),
+            recipe: selected_recipe
+                .map(|selected| selected.id())
+                .or(site.and_then(|site| site.recipe)),
+        });
+
+        demolitions.write(Demolished {
+            entity: demolishable,
+            construct,
+            coord: Coord(coord.0),
+        });
+    }
+
+    if !removed.is_empty() {
+        history.record(HistoryStep::Constructs {
+            placed: Vec::new(),
+            removed,
+        });
     }
 }
 
 fn refund_on_demolition(
     mut demolished: MessageReader<Demolished>,
     structure_defs: Res<Assets<StructureDef>>,
+    path_defs: Res<Assets<PathDef>>,
     player: Single<Entity, With<Player>>,
     inventory: Query<&Inventory>,
     mut stacks: Query<&mut ItemStack>,
 ) {
-    for Demolished { structure, .. } in demolished.read() {
-        let Some(structure_def) = structure_defs.get(structure) else {
+    for Demolished { construct, .. } in demolished.read() {
+        let cost = match construct {
+            ConstructKind::Structure(handle) => structure_defs.get(handle).map(|def| &def.cost),
+            ConstructKind::Path(handle) => path_defs.get(handle).map(|def| &def.cost),
+            ConstructKind::Site { delivered, .. } => Some(delivered),
+        };
+
+        let Some(cost) = cost else {
             continue;
         };
 
-        refund(*player, &structure_def.cost, &inventory, &mut stacks);
+        refund(*player, cost, &inventory, &mut stacks);
     }
 }
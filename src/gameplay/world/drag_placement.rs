@@ -0,0 +1,436 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_aseprite_ultra::prelude::*;
+
+use crate::{
+    gameplay::{
+        hud::hotbar::{HotbarActionKind, HotbarSelection},
+        inventory::prelude::*,
+        player::Player,
+        sprite_sort::{YSortSprite, ZIndexSprite},
+        structure::{
+            assets::StructureDef,
+            footprint::Footprint,
+            orientation::{Orientation, PlacementOrientation},
+            path::{PathDef, PathSegment, segment_slice_name},
+        },
+        world::{
+            construction::{
+                Constructions, PlacementSurroundings, placement_allowed, placement_cost,
+            },
+            tilemap::{
+                TileClicked,
+                coord::{Coord, translation_to_coord},
+            },
+        },
+    },
+    input::cursor::CursorPosition,
+    widgets::tooltip::{HideTooltip, ShowTooltip, TooltipSource},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DragPlacement>();
+
+    app.add_observer(start_drag_placement);
+    app.add_observer(finish_drag_placement);
+
+    app.add_systems(
+        Update,
+        (
+            update_drag_run,
+            refresh_run_preview.run_if(resource_changed::<DragPlacement>),
+        )
+            .chain(),
+    );
+}
+
+/// State of a placement run dragged out with the primary pointer button
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct DragPlacement {
+    pub start: Option<IVec2>,
+    pub run: Vec<IVec2>,
+    pub cost: HashMap<Handle<ItemDef>, u32>,
+    pub affordable: bool,
+}
+
+impl DragPlacement {
+    pub fn is_dragging(&self) -> bool {
+        self.start.is_some()
+    }
+}
+
+#[derive(Component, Reflect, Debug, Default)]
+#[reflect(Component)]
+pub struct RunPreview;
+
+/// Tiles covered by a run from `start` to `end`. Runs along the longest axis first, forming a
+/// straight line when both points are aligned and an L-shape otherwise.
+pub fn placement_run(start: IVec2, end: IVec2) -> Vec<IVec2> {
+    let delta = end - start;
+
+    let corner = if delta.x.abs() >= delta.y.abs() {
+        IVec2::new(end.x, start.y)
+    } else {
+        IVec2::new(start.x, end.y)
+    };
+
+    let mut run = vec![start];
+
+    for (from, to) in [(start, corner), (corner, end)] {
+        let step = (to - from).signum();
+        let mut pos = from;
+
+        while pos != to {
+            pos += step;
+            run.push(pos);
+        }
+    }
+
+    run
+}
+
+/// Tiles of the run a construct will actually be placed on. Constructs are placed one after the
+/// other along the run, so those overlapping one placed before them are left out.
+fn buildable_tiles(
+    run: &[IVec2],
+    action: &HotbarActionKind,
+    orientation: &Orientation,
+    structure_defs: &Assets<StructureDef>,
+    constructions: &Constructions,
+    path_segments: &Query<&PathSegment>,
+    surroundings: &PlacementSurroundings,
+) -> Vec<IVec2> {
+    let footprint = match action {
+        HotbarActionKind::PlaceStructure(handle) => structure_defs
+            .get(handle)
+            .map(|structure_def| orientation.footprint(&structure_def.footprint))
+            .unwrap_or_default(),
+        HotbarActionKind::PlacePath(_) => Footprint::default(),
+    };
+
+    without_overlaps(run, &footprint, |coord| {
+        placement_allowed(
+            action,
+            coord,
+            orientation,
+            structure_defs,
+            constructions,
+            path_segments,
+            surroundings,
+        )
+    })
+}
+
+/// Tiles of the run a construct is allowed on whose footprint doesn't overlap one kept earlier
+fn without_overlaps(
+    run: &[IVec2],
+    footprint: &Footprint,
+    allowed: impl Fn(&IVec2) -> bool,
+) -> Vec<IVec2> {
+    let mut covered = HashSet::new();
+
+    run.iter()
+        .copied()
+        .filter(|coord| allowed(coord))
+        .filter(|coord| {
+            let tiles: Vec<IVec2> = footprint.tiles(*coord).collect();
+
+            if tiles.iter().any(|tile| covered.contains(tile)) {
+                return false;
+            }
+
+            covered.extend(tiles);
+            true
+        })
+        .collect()
+}
+
+fn start_drag_placement(
+    drag_start: On<Pointer<DragStart>>,
+    ui_nodes: Query<(), With<Node>>,
+    hotbar_selection: HotbarSelection,
+    cursor_position: Res<CursorPosition>,
+    mut drag_placement: ResMut<DragPlacement>,
+) {
+    if drag_start.button != PointerButton::Primary || ui_nodes.contains(drag_start.entity) {
+        return;
+    }
+
+    if hotbar_selection.action().is_none() || drag_placement.is_dragging() {
+        return;
+    }
+
+    drag_placement.start = Some(translation_to_coord(&cursor_position.0).0);
+}
+
+fn update_drag_run(
+    cursor_position: Res<CursorPosition>,
+    mut drag_placement: ResMut<DragPlacement>,
+    hotbar_selection: HotbarSelection,
+    structure_defs: Res<Assets<StructureDef>>,
+    path_defs: Res<Assets<PathDef>>,
+    constructions: Res<Constructions>,
+    path_segments: Query<&PathSegment>,
+    surroundings: PlacementSurroundings,
+    placement_orientation: Res<PlacementOrientation>,
+    player: Single<Entity, With<Player>>,
+    inventory: Query<&Inventory>,
+    stacks: Query<&mut ItemStack>,
+) {
+    let Some(start) = drag_placement.start else {
+        return;
+    };
+
+    let run = placement_run(start, translation_to_coord(&cursor_position.0).0);
+
+    if run == drag_placement.run {
+        return;
+    }
+
+    let Some(action) = hotbar_selection.action() else {
+        return;
+    };
+
+    let unit_cost = placement_cost(action, &structure_defs, &path_defs)
+        .cloned()
+        .unwrap_or_default();
+
+    let placeable = buildable_tiles(
+        &run,
+        action,
+        &placement_orientation.0,
+        &structure_defs,
+        &constructions,
+        &path_segments,
+        &surroundings,
+    )
+    .len() as u32;
+
+    let cost: HashMap<Handle<ItemDef>, u32> = unit_cost
+        .into_iter()
+        .map(|(item, quantity)| (item, quantity * placeable))
+        .collect();
+
+    drag_placement.affordable = can_afford(*player, &cost, &inventory, &stacks);
+    drag_placement.cost = cost;
+    drag_placement.run = run;
+}
+
+fn refresh_run_preview(
+    mut commands: Commands,
+    drag_placement: Res<DragPlacement>,
+    previews: Query<Entity, With<RunPreview>>,
+    hotbar_selection: HotbarSelection,
+    structure_defs: Res<Assets<StructureDef>>,
+    path_defs: Res<Assets<PathDef>>,
+    item_defs: Res<Assets<ItemDef>>,
+    constructions: Res<Constructions>,
+    path_segments: Query<&PathSegment>,
+    surroundings: PlacementSurroundings,
+    placement_orientation: Res<PlacementOrientation>,
+    asset_server: Res<AssetServer>,
+) {
+    for preview in previews {
+        commands.entity(preview).despawn();
+    }
+
+    if drag_placement.run.is_empty() {
+        return;
+    }
+
+    let Some(action) = hotbar_selection.action() else {
+        return;
+    };
+
+    let buildable = buildable_tiles(
+        &drag_placement.run,
+        action,
+        &placement_orientation.0,
+        &structure_defs,
+        &constructions,
+        &path_segments,
+        &surroundings,
+    );
+
+    for coord in drag_placement.run.iter() {
+        let allowed = buildable.contains(coord);
+
+        let color = if !allowed || !drag_placement.affordable {
+            Color::hsl(0.0, 1.0, 0.5)
+        } else {
+            Color::default().with_alpha(0.5)
+        };
+
+        let preview = commands
+            .spawn((
+                Name::new("Run Preview"),
+                RunPreview,
+                Coord(*coord),
+                Sprite { color, ..default() },
+                YSortSprite,
+                ZIndexSprite(10),
+            ))
+            .id();
+
+        match action {
+            HotbarActionKind::PlaceStructure(handle) => {
+                let Some(structure_def) = structure_defs.get(handle) else {
+                    continue;
+                };
+
+                let orientation = placement_orientation.0;
+
+                commands.entity(preview).insert((
+                    orientation.footprint(&structure_def.footprint),
+                    Sprite {
+                        color,
+                        flip_x: orientation.flips_sprite(),
+                        ..default()
+                    },
+                    Anchor(Vec2::new(0.0, -0.33)),
+                    AseAnimation {
+                        aseprite: asset_server.load(&structure_def.sprite),
+                        animation: Animation::tag("work"),
+                    },
+                ));
+            }
+            HotbarActionKind::PlacePath(handle) => {
+                let Some(path_def) = path_defs.get(handle) else {
+                    continue;
+                };
+
+                let name = segment_slice_name(*coord, |c| {
+                    drag_placement.run.contains(&c) || constructions.contains_key(&c)
+                });
+
+                commands.entity(preview).insert(AseSlice {
+                    aseprite: asset_server.load(&path_def.sprite),
+                    name,
+                });
+            }
+        }
+    }
+
+    let mut lines = vec![format!("{} tiles", drag_placement.run.len())];
+
+    for (item, quantity) in drag_placement.cost.iter() {
+        if let Some(item_def) = item_defs.get(item) {
+            lines.push(format!("{}: {quantity}", item_def.name));
+        }
+    }
+
+    commands.trigger(ShowTooltip {
+        source: TooltipSource::DragPlacement,
+        text: lines.join("\n"),
+    });
+}
+
+fn finish_drag_placement(
+    drag_end: On<Pointer<DragEnd>>,
+    mut drag_placement: ResMut<DragPlacement>,
+    hotbar_selection: HotbarSelection,
+    structure_defs: Res<Assets<StructureDef>>,
+    constructions: Res<Constructions>,
+    path_segments: Query<&PathSegment>,
+    surroundings: PlacementSurroundings,
+    placement_orientation: Res<PlacementOrientation>,
+    mut tile_clicks: MessageWriter<TileClicked>,
+    mut commands: Commands,
+) {
+    if drag_end.button != PointerButton::Primary || !drag_placement.is_dragging() {
+        return;
+    }
+
+    if drag_placement.affordable
+        && let Some(action) = hotbar_selection.action()
+    {
+        for coord in buildable_tiles(
+            &drag_placement.run,
+            action,
+            &placement_orientation.0,
+            &structure_defs,
+            &constructions,
+            &path_segments,
+            &surroundings,
+        ) {
+            tile_clicks.write(TileClicked(Coord(coord)));
+        }
+    }
+
+    *drag_placement = DragPlacement::default();
+
+    commands.trigger(HideTooltip(TooltipSource::DragPlacement));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_single_tile() {
+        let origin = IVec2::new(3, -2);
+
+        assert_eq!(placement_run(origin, origin), vec![origin]);
+    }
+
+    #[test]
+    fn test_run_straight_line() {
+        let run = placement_run(IVec2::new(0, 0), IVec2::new(0, -3));
+
+        assert_eq!(
+            run,
+            vec![
+                IVec2::new(0, 0),
+                IVec2::new(0, -1),
+                IVec2::new(0, -2),
+                IVec2::new(0, -3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_l_shape_follows_longest_axis_first() {
+        let run = placement_run(IVec2::new(0, 0), IVec2::new(3, 1));
+
+        assert_eq!(
+            run,
+            vec![
+                IVec2::new(0, 0),
+                IVec2::new(1, 0),
+                IVec2::new(2, 0),
+                IVec2::new(3, 0),
+                IVec2::new(3, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overlapping_placements_are_left_out() {
+        let run = placement_run(IVec2::ZERO, IVec2::new(4, 0));
+        let footprint = Footprint(vec![IVec2::ZERO, IVec2::X]);
+        let blocked = IVec2::new(2, 0);
+
+        let buildable = without_overlaps(&run, &footprint, |coord| *coord != blocked);
+
+        assert_eq!(buildable, vec![IVec2::new(0, 0), IVec2::new(3, 0)]);
+    }
+
+    #[test]
+    fn test_run_is_contiguous() {
+        let run = placement_run(IVec2::new(-4, 2), IVec2::new(1, 7));
+
+        assert_eq!(run.len(), 11);
+
+        for pair in run.windows(2) {
+            assert_eq!(
+                pair[0].manhattan_distance(pair[1]),
+                1,
+                "Expected {:?} and {:?} to be neighbours",
+                pair[0],
+                pair[1],
+            );
+        }
+    }
+}
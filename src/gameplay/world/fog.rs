@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use bevy::{color::Luminance, prelude::*};
+use bevy_ecs_tilemap::prelude::*;
+
+use crate::{
+    gameplay::{
+        people::porting::Porting,
+        structure::{
+            deposit::Deposit, footprint::Footprint, obstacle::Obstacle, range::Range, town::Town,
+        },
+        world::{
+            biome::Biomes,
+            construction::Constructions,
+            generation::WorldGen,
+            tilemap::{
+                chunk::ChunkManager,
+                coord::{Coord, translation_to_coord},
+            },
+        },
+    },
+    screens::Screen,
+};
+
+/// Radius of the region around the origin revealed when a game starts
+pub const STARTING_REVEAL: i32 = 12;
+
+/// Radius revealed around a porter on the move
+pub const PORTER_SIGHT: i32 = 2;
+
+/// How much darker unrevealed tiles are drawn than revealed ones
+const OBSCURED_DARKNESS: f32 = 0.6;
+
+/// Tiles covered by each revealed mask, independent of the chunk size generation uses
+const MASK_SIZE: UVec2 = UVec2::new(16, 16);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<FogOfWar>();
+    app.add_message::<TileRevealed>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reveal_starting_region);
+
+    app.add_systems(FixedUpdate, (reveal_around_sights, reveal_around_porters));
+
+    app.add_systems(
+        Update,
+        (show_revealed_tiles, show_revealed_constructs).run_if(on_message::<TileRevealed>),
+    );
+}
+
+/// Area around an entity it reveals, in addition to the tiles it covers
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Sight(pub Range);
+
+#[derive(Message, Reflect, Debug)]
+pub struct TileRevealed(pub IVec2);
+
+/// Tiles of a square of the world that have been revealed, one bit per tile
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct RevealedMask(Vec<u64>);
+
+impl Default for RevealedMask {
+    fn default() -> Self {
+        let tiles = (MASK_SIZE.x * MASK_SIZE.y) as usize;
+        Self(vec![0; tiles.div_ceil(64)])
+    }
+}
+
+impl RevealedMask {
+    fn bit(local: UVec2) -> (usize, u64) {
+        let index = (local.y * MASK_SIZE.x + local.x) as usize;
+        (index / 64, 1 << (index % 64))
+    }
+
+    pub fn contains(&self, local: UVec2) -> bool {
+        let (word, bit) = Self::bit(local);
+        self.0[word] & bit != 0
+    }
+
+    /// Marks the tile revealed, returning whether it wasn't already
+    pub fn insert(&mut self, local: UVec2) -> bool {
+        let (word, bit) = Self::bit(local);
+        let newly_revealed = self.0[word] & bit == 0;
+        self.0[word] |= bit;
+        newly_revealed
+    }
+}
+
+/// Revealed tiles of the world. Chunks are despawned when out of view and regenerated from the
+/// seed, so the masks are kept here rather than on them, and outlive unloaded chunks.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct FogOfWar {
+    chunks: HashMap<IVec2, RevealedMask>,
+}
+
+impl FogOfWar {
+    /// Mask covering the tile and the tile's position within that mask
+    fn locate(coord: IVec2) -> (IVec2, UVec2) {
+        let size = MASK_SIZE.as_ivec2();
+        (coord.div_euclid(size), coord.rem_euclid(size).as_uvec2())
+    }
+
+    pub fn is_revealed(&self, coord: IVec2) -> bool {
+        let (chunk, local) = Self::locate(coord);
+        self.chunks
+            .get(&chunk)
+            .is_some_and(|mask| mask.contains(local))
+    }
+
+    /// Marks the tile revealed, returning whether it wasn't already
+    pub fn reveal(&mut self, coord: IVec2) -> bool {
+        let (chunk, local) = Self::locate(coord);
+        self.chunks.entry(chunk).or_default().insert(local)
+    }
+}
+
+/// Colour a tile of the given tint is drawn with, dimmed while the tile is unrevealed
+pub fn tile_color(tint: Color, revealed: bool) -> Color {
+    if revealed {
+        tint
+    } else {
+        tint.darker(OBSCURED_DARKNESS)
+    }
+}
+
+fn reveal(
+    fog: &mut FogOfWar,
+    tiles: impl IntoIterator<Item = IVec2>,
+    revealed: &mut MessageWriter<TileRevealed>,
+) {
+    for tile in tiles {
+        if fog.reveal(tile) {
+            revealed.write(TileRevealed(tile));
+        }
+    }
+}
+
+fn reveal_starting_region(mut fog: ResMut<FogOfWar>, mut revealed: MessageWriter<TileRevealed>) {
+    *fog = FogOfWar::default();
+
+    let origin = IVec2::ZERO;
+    let tiles = Range::Diamond(STARTING_REVEAL).iter(origin).chain([origin]);
+    reveal(&mut fog, tiles, &mut revealed);
+}
+
+fn reveal_around_sights(
+    sights: Query<(&Coord, Option<&Footprint>, &Sight), Or<(Added<Sight>, Changed<Coord>)>>,
+    mut fog: ResMut<FogOfWar>,
+    mut revealed: MessageWriter<TileRevealed>,
+) {
+    for (coord, footprint, sight) in sights {
+        let covered: Vec<IVec2> = match footprint {
+            Some(footprint) => footprint.tiles(coord.0).collect(),
+            None => vec![coord.0],
+        };
+
+        let tiles = covered
+            .iter()
+            .flat_map(|tile| sight.0.iter(*tile).chain([*tile]))
+            .collect::<Vec<_>>();
+
+        reveal(&mut fog, tiles, &mut revealed);
+    }
+}
+
+fn reveal_around_porters(
+    porters: Query<&Transform, With<Porting>>,
+    mut fog: ResMut<FogOfWar>,
+    mut revealed: MessageWriter<TileRevealed>,
+) {
+    for transform in porters {
+        let coord = translation_to_coord(&transform.translation.xy()).0;
+        let tiles = Range::Diamond(PORTER_SIGHT).iter(coord).chain([coord]);
+
+        reveal(&mut fog, tiles, &mut revealed);
+    }
+}
+
+fn show_revealed_tiles(
+    mut revealed: MessageReader<TileRevealed>,
+    chunk_manager: Res<ChunkManager>,
+    children: Query<&Children>,
+    storages: Query<&TileStorage>,
+    mut colors: Query<&mut TileColor>,
+    biomes: Biomes,
+    world_gen: Res<WorldGen>,
+) {
+    for TileRevealed(coord) in revealed.read() {
+        let (chunk, local) = world_gen.locate(*coord);
+        let Some(chunk) = chunk_manager.spawned_chunks.get(&chunk) else {
+            continue;
+        };
+
+        let Some(biome) = biomes.get(*coord) else {
+            continue;
+        };
+
+        let position = TilePos::new(local.x, local.y);
+
+        for layer in children.get(*chunk).into_iter().flatten() {
+            let Some(tile) = storages
+                .get(*layer)
+                .ok()
+                .and_then(|storage| storage.get(&position))
+            else {
+                continue;
+            };
+
+            if let Ok(mut color) = colors.get_mut(tile) {
+                color.0 = biome.ground_tint(world_gen.terrain(*coord));
+            }
+        }
+    }
+}
+
+/// Shows the generated constructs hidden on tiles until they were revealed
+fn show_revealed_constructs(
+    mut revealed: MessageReader<TileRevealed>,
+    constructions: Res<Constructions>,
+    mut hidden: Query<&mut Visibility, Or<(With<Deposit>, With<Obstacle>, With<Town>)>>,
+) {
+    for TileRevealed(coord) in revealed.read() {
+        let Some(mut visibility) = constructions
+            .get(coord)
+            .and_then(|entity| hidden.get_mut(*entity).ok())
+        else {
+            continue;
+        };
+
+        *visibility = Visibility::Inherited;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fog_of_war_reveals_tiles_once() {
+        let mut fog = FogOfWar::default();
+        let tile = IVec2::new(-1, 17);
+
+        assert!(!fog.is_revealed(tile));
+        assert!(fog.reveal(tile));
+        assert!(!fog.reveal(tile));
+        assert!(fog.is_revealed(tile));
+        assert!(!fog.is_revealed(IVec2::new(15, 17)));
+
+        assert!(fog.chunks[&IVec2::new(-1, 1)].contains(UVec2::new(15, 1)));
+    }
+}
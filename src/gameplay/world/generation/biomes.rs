@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use noise::{NoiseFn, OpenSimplex};
+
+use crate::gameplay::world::generation::{ChunkGenerator, ChunkPlan, WorldGenConfig, WorldSpec};
+
+const TEMPERATURE_SALT: u32 = 193_847;
+const MOISTURE_SALT: u32 = 582_013;
+
+/// Climate noise deciding which biome each tile belongs to
+#[derive(Clone)]
+pub struct BiomeMap {
+    temperature: OpenSimplex,
+    moisture: OpenSimplex,
+    scale: f64,
+    /// Typical climate of each biome of the world spec
+    climates: Vec<Vec2>,
+}
+
+impl BiomeMap {
+    pub fn new(seed: u32, config: &WorldGenConfig, spec: &WorldSpec) -> Self {
+        Self {
+            temperature: OpenSimplex::new(seed ^ TEMPERATURE_SALT),
+            moisture: OpenSimplex::new(seed ^ MOISTURE_SALT),
+            scale: config.climate_scale,
+            climates: spec.biomes.iter().map(|biome| biome.climate).collect(),
+        }
+    }
+
+    /// Temperature and moisture at the coordinate
+    pub fn climate(&self, coord: IVec2) -> Vec2 {
+        let point: [f64; 2] = (coord.as_dvec2() * self.scale).into();
+
+        Vec2::new(
+            self.temperature.get(point) as f32,
+            self.moisture.get(point) as f32,
+        )
+    }
+
+    /// Index of the biome covering the coordinate in the world spec, if there are any biomes
+    pub fn biome_at(&self, coord: IVec2) -> Option<usize> {
+        nearest_biome(self.climate(coord), &self.climates)
+    }
+}
+
+/// Biome whose typical climate is closest, the first one listed winning ties
+fn nearest_biome(climate: Vec2, climates: &[Vec2]) -> Option<usize> {
+    climates
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            climate
+                .distance_squared(**a)
+                .total_cmp(&climate.distance_squared(**b))
+        })
+        .map(|(index, _)| index)
+}
+
+/// Assigns every tile the biome of its climate
+pub struct BiomeGenerator {
+    map: BiomeMap,
+}
+
+impl BiomeGenerator {
+    pub fn new(seed: u32, config: &WorldGenConfig, spec: &WorldSpec) -> Self {
+        Self {
+            map: BiomeMap::new(seed, config, spec),
+        }
+    }
+}
+
+impl ChunkGenerator for BiomeGenerator {
+    fn generate(&self, plan: &mut ChunkPlan) {
+        for (coord, tile) in plan.iter_mut() {
+            tile.biome = self.map.biome_at(coord);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_biome_prefers_first_on_ties() {
+        let climates = [Vec2::new(-0.5, 0.0), Vec2::new(0.5, 0.0)];
+
+        assert_eq!(nearest_biome(Vec2::new(0.4, 0.9), &climates), Some(1));
+        assert_eq!(nearest_biome(Vec2::new(0.0, 0.9), &climates), Some(0));
+        assert_eq!(nearest_biome(Vec2::ZERO, &[]), None);
+    }
+}
@@ -0,0 +1,100 @@
+use noise::{NoiseFn, OpenSimplex};
+
+use crate::gameplay::{
+    structure::deposit::DepositGeneration,
+    world::{
+        generation::{ChunkGenerator, ChunkPlan, PlannedConstruct, Terrain, WorldSpec},
+        tilemap::coord::tile_hash,
+    },
+};
+
+/// Places deposits where their noise peaks, on tiles left empty
+pub struct DepositGenerator {
+    seed: u32,
+    biome_ids: Vec<String>,
+    deposits: Vec<(DepositGeneration, OpenSimplex)>,
+}
+
+impl DepositGenerator {
+    pub fn new(seed: u32, spec: &WorldSpec) -> Self {
+        Self {
+            seed,
+            biome_ids: spec.biomes.iter().map(|biome| biome.id.clone()).collect(),
+            deposits: spec
+                .deposits
+                .iter()
+                .map(|deposit| {
+                    let noise = OpenSimplex::new(seed ^ deposit.generation.seed);
+                    (deposit.generation.clone(), noise)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ChunkGenerator for DepositGenerator {
+    fn generate(&self, plan: &mut ChunkPlan) {
+        for (coord, tile) in plan.iter_mut() {
+            if tile.construct.is_some() || tile.terrain == Terrain::River {
+                continue;
+            }
+
+            let biome = tile
+                .biome
+                .and_then(|biome| self.biome_ids.get(biome))
+                .map(String::as_str);
+
+            let candidates = self
+                .deposits
+                .iter()
+                .enumerate()
+                .filter(|(_, (generation, _))| {
+                    !generation.riverbank || tile.terrain == Terrain::Riverbank
+                })
+                .filter_map(|(index, (generation, noise))| {
+                    let value = noise.get((coord.as_dvec2() * generation.noise_scale).into());
+                    let roll =
+                        tile_hash(coord, self.seed ^ generation.seed) as f32 / u32::MAX as f32;
+                    let strength = generation.strength(value, biome, roll)?;
+
+                    Some((strength, index))
+                });
+
+            let Some((strength, deposit)) = strongest_deposit(candidates) else {
+                continue;
+            };
+
+            tile.construct = Some(PlannedConstruct::Deposit {
+                deposit,
+                quantity: self.deposits[deposit].0.quantity(strength),
+            });
+        }
+    }
+}
+
+/// Deposit claiming a tile several deposits could appear on, the strongest winning and ties going
+/// to the one listed first. Deposits are listed by id, so the outcome doesn't depend on the order
+/// their manifests were loaded in.
+fn strongest_deposit(candidates: impl IntoIterator<Item = (f64, usize)>) -> Option<(f64, usize)> {
+    candidates
+        .into_iter()
+        .max_by(|(strength_a, index_a), (strength_b, index_b)| {
+            strength_a.total_cmp(strength_b).then(index_b.cmp(index_a))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strongest_deposit_ignores_candidate_order() {
+        let candidates = [(0.5, 1), (0.5, 0), (0.2, 2)];
+
+        assert_eq!(strongest_deposit(candidates), Some((0.5, 0)));
+        assert_eq!(
+            strongest_deposit(candidates.into_iter().rev()),
+            Some((0.5, 0))
+        );
+    }
+}
@@ -0,0 +1,514 @@
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    assets::{
+        loaders::toml::{FromToml, TomlAssetPlugin},
+        tracking::LoadResource,
+    },
+    gameplay::{
+        random::Seed,
+        structure::{
+            deposit::{DepositDef, DepositGeneration},
+            obstacle::ObstacleDef,
+            town::TownDef,
+        },
+        world::{
+            biome::BiomeDef,
+            generation::{
+                biomes::{BiomeGenerator, BiomeMap},
+                deposits::DepositGenerator,
+                obstacles::ObstacleGenerator,
+                rivers::{RiverGenerator, RiverMap},
+                towns::TownGenerator,
+            },
+        },
+    },
+    screens::Screen,
+};
+
+pub mod biomes;
+pub mod deposits;
+pub mod obstacles;
+pub mod preview;
+pub mod rivers;
+pub mod towns;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(TomlAssetPlugin::<WorldGenConfig>::extensions(&[
+        "worldgen.toml",
+    ]));
+    app.load_resource::<WorldGenAssets>();
+
+    app.init_resource::<WorldGen>();
+    app.add_systems(OnEnter(Screen::Gameplay), create_world_gen);
+}
+
+#[derive(Asset, Resource, Reflect, Clone)]
+#[reflect(Resource)]
+pub struct WorldGenAssets {
+    config: Handle<WorldGenConfig>,
+}
+
+impl FromWorld for WorldGenAssets {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        Self {
+            config: asset_server.load("manifests/worldgen.toml"),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct WorldGenRaw {
+    pub seed: Option<u32>,
+    pub chunk_size: [u32; 2],
+    pub render_distance: [u32; 2],
+    pub climate_scale: f64,
+    pub river_scale: f64,
+    pub river_width: f64,
+    pub town_spacing: u32,
+    pub town_chance: f32,
+    pub stages: Vec<Stage>,
+}
+
+impl Default for WorldGenRaw {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            chunk_size: [16, 16],
+            render_distance: [3, 3],
+            climate_scale: 0.02,
+            river_scale: 0.012,
+            river_width: 0.015,
+            town_spacing: 48,
+            town_chance: 0.5,
+            stages: vec![
+                Stage::Biomes,
+                Stage::Rivers,
+                Stage::Towns,
+                Stage::Obstacles,
+                Stage::Deposits,
+            ],
+        }
+    }
+}
+
+#[derive(Asset, Reflect, Debug, Clone)]
+pub struct WorldGenConfig {
+    /// Seed every stage derives its randomness from, picked at random for each game if unset
+    pub seed: Option<u32>,
+    /// Tiles in each chunk, the unit the world is generated and loaded in
+    pub chunk_size: UVec2,
+    /// Chunks kept loaded around the camera in each direction
+    pub render_distance: UVec2,
+    /// Scale applied to tile coordinates when sampling climate, larger values giving smaller biomes
+    pub climate_scale: f64,
+    /// Scale applied to tile coordinates when sampling river noise, larger values giving more
+    /// winding rivers
+    pub river_scale: f64,
+    /// How close to zero the river noise has to be for a tile to be river, larger values giving
+    /// wider rivers
+    pub river_width: f64,
+    /// Tiles between the centres of neighbouring cells of the town grid, each holding at most
+    /// one town
+    pub town_spacing: u32,
+    /// Chance of a cell of the town grid holding a town
+    pub town_chance: f32,
+    /// Generators run on each chunk, in order
+    pub stages: Vec<Stage>,
+}
+
+impl From<WorldGenRaw> for WorldGenConfig {
+    fn from(raw: WorldGenRaw) -> Self {
+        Self {
+            seed: raw.seed,
+            chunk_size: UVec2::from(raw.chunk_size),
+            render_distance: UVec2::from(raw.render_distance),
+            climate_scale: raw.climate_scale,
+            river_scale: raw.river_scale,
+            river_width: raw.river_width,
+            town_spacing: raw.town_spacing,
+            town_chance: raw.town_chance,
+            stages: raw.stages,
+        }
+    }
+}
+
+impl Default for WorldGenConfig {
+    fn default() -> Self {
+        WorldGenRaw::default().into()
+    }
+}
+
+impl FromToml for WorldGenConfig {
+    type Raw = WorldGenRaw;
+
+    fn from_toml(raw: Self::Raw, _load_context: &mut bevy::asset::LoadContext) -> Self {
+        raw.into()
+    }
+}
+
+/// Step of world generation, each filling in part of a chunk's plan
+#[derive(Reflect, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Biomes,
+    Rivers,
+    Towns,
+    Obstacles,
+    Deposits,
+}
+
+impl Stage {
+    fn generator(
+        &self,
+        seed: u32,
+        config: &WorldGenConfig,
+        spec: &WorldSpec,
+    ) -> Box<dyn ChunkGenerator> {
+        match self {
+            Stage::Biomes => Box::new(BiomeGenerator::new(seed, config, spec)),
+            Stage::Rivers => Box::new(RiverGenerator::new(seed, config)),
+            Stage::Towns => Box::new(TownGenerator::new(seed, config, spec)),
+            Stage::Obstacles => Box::new(ObstacleGenerator::new(seed, spec)),
+            Stage::Deposits => Box::new(DepositGenerator::new(seed, spec)),
+        }
+    }
+}
+
+/// Stage of the generation pipeline, filling in a chunk's plan from what earlier stages left in it
+pub trait ChunkGenerator: Send + Sync {
+    fn generate(&self, plan: &mut ChunkPlan);
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct BiomeSpec {
+    pub id: String,
+    pub climate: Vec2,
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct ObstacleSpec {
+    pub id: String,
+    /// Fraction of tiles covered by the obstacle in each biome, by id
+    pub biomes: std::collections::HashMap<String, f32>,
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct TownSpec {
+    pub id: String,
+    pub population: [u32; 2],
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub struct DepositSpec {
+    pub id: String,
+    pub generation: DepositGeneration,
+}
+
+/// Everything generation needs to know from the manifests, each list ordered by id so the result
+/// doesn't depend on the order manifests were loaded in
+#[derive(Reflect, Debug, Clone, Default)]
+pub struct WorldSpec {
+    pub biomes: Vec<BiomeSpec>,
+    pub obstacles: Vec<ObstacleSpec>,
+    pub deposits: Vec<DepositSpec>,
+    pub towns: Vec<TownSpec>,
+}
+
+impl WorldSpec {
+    pub fn new(
+        mut biomes: Vec<BiomeSpec>,
+        mut obstacles: Vec<ObstacleSpec>,
+        mut deposits: Vec<DepositSpec>,
+        mut towns: Vec<TownSpec>,
+    ) -> Self {
+        biomes.sort_by(|a, b| a.id.cmp(&b.id));
+        obstacles.sort_by(|a, b| a.id.cmp(&b.id));
+        deposits.sort_by(|a, b| a.id.cmp(&b.id));
+        towns.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Self {
+            biomes,
+            obstacles,
+            deposits,
+            towns,
+        }
+    }
+}
+
+/// Construct generation placed on a tile, by index into the world spec
+#[derive(Reflect, Debug, Clone, Copy, PartialEq)]
+pub enum PlannedConstruct {
+    Obstacle(usize),
+    Deposit { deposit: usize, quantity: u32 },
+    Town { town: usize, population: u32 },
+}
+
+/// Lay of the land on a tile, besides its biome
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Terrain {
+    #[default]
+    Land,
+    /// Land bordering a river
+    Riverbank,
+    /// Water nothing but bridges can be built over
+    River,
+}
+
+#[derive(Reflect, Debug, Clone, Default, PartialEq)]
+pub struct TilePlan {
+    /// Index of the tile's biome in the world spec
+    pub biome: Option<usize>,
+    pub terrain: Terrain,
+    pub construct: Option<PlannedConstruct>,
+}
+
+/// What generation decided for every tile of a chunk
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component)]
+pub struct ChunkPlan {
+    pub chunk: IVec2,
+    pub size: UVec2,
+    tiles: Vec<TilePlan>,
+}
+
+impl ChunkPlan {
+    pub fn new(chunk: IVec2, size: UVec2) -> Self {
+        Self {
+            chunk,
+            size,
+            tiles: vec![TilePlan::default(); (size.x * size.y) as usize],
+        }
+    }
+
+    /// Coordinate of the chunk's first tile
+    pub fn origin(&self) -> IVec2 {
+        self.chunk * self.size.as_ivec2()
+    }
+
+    fn local(&self, index: usize) -> UVec2 {
+        UVec2::new(index as u32 % self.size.x, index as u32 / self.size.x)
+    }
+
+    /// Every tile with its position within the chunk and its coordinate
+    pub fn iter(&self) -> impl Iterator<Item = (UVec2, IVec2, &TilePlan)> {
+        let origin = self.origin();
+
+        self.tiles.iter().enumerate().map(move |(index, tile)| {
+            let local = self.local(index);
+            (local, origin + local.as_ivec2(), tile)
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (IVec2, &mut TilePlan)> {
+        let origin = self.origin();
+        let width = self.size.x;
+
+        self.tiles.iter_mut().enumerate().map(move |(index, tile)| {
+            let local = IVec2::new((index as u32 % width) as i32, (index as u32 / width) as i32);
+            (origin + local, tile)
+        })
+    }
+}
+
+/// Generators of every stage, run in order on each chunk
+#[derive(Default)]
+pub struct Pipeline {
+    chunk_size: UVec2,
+    stages: Vec<Box<dyn ChunkGenerator>>,
+}
+
+impl Pipeline {
+    pub fn new(config: &WorldGenConfig, seed: u32, spec: &WorldSpec) -> Self {
+        Self {
+            chunk_size: config.chunk_size,
+            stages: config
+                .stages
+                .iter()
+                .map(|stage| stage.generator(seed, config, spec))
+                .collect(),
+        }
+    }
+
+    pub fn generate(&self, chunk: IVec2) -> ChunkPlan {
+        let mut plan = ChunkPlan::new(chunk, self.chunk_size);
+
+        for stage in self.stages.iter() {
+            stage.generate(&mut plan);
+        }
+
+        plan
+    }
+}
+
+/// World generation of the current game
+#[derive(Resource)]
+pub struct WorldGen {
+    pub config: WorldGenConfig,
+    pub spec: WorldSpec,
+    pub pipeline: Pipeline,
+    /// Biome lookup matching the biomes generated, for tiles of any chunk
+    pub biome_map: BiomeMap,
+    /// River lookup matching the rivers generated, if the rivers stage runs at all
+    pub river_map: Option<RiverMap>,
+}
+
+impl Default for WorldGen {
+    fn default() -> Self {
+        Self::new(WorldGenConfig::default(), 0, WorldSpec::default())
+    }
+}
+
+impl WorldGen {
+    pub fn new(config: WorldGenConfig, seed: u32, spec: WorldSpec) -> Self {
+        Self {
+            pipeline: Pipeline::new(&config, seed, &spec),
+            biome_map: BiomeMap::new(seed, &config, &spec),
+            river_map: config
+                .stages
+                .contains(&Stage::Rivers)
+                .then(|| RiverMap::new(seed, &config)),
+            config,
+            spec,
+        }
+    }
+
+    /// Chunk containing the coordinate and the tile's position within that chunk
+    pub fn locate(&self, coord: IVec2) -> (IVec2, UVec2) {
+        let size = self.config.chunk_size.as_ivec2();
+        (coord.div_euclid(size), coord.rem_euclid(size).as_uvec2())
+    }
+
+    /// Terrain generated at the coordinate, for tiles of any chunk
+    pub fn terrain(&self, coord: IVec2) -> Terrain {
+        self.river_map
+            .as_ref()
+            .map_or(Terrain::Land, |river_map| river_map.terrain(coord))
+    }
+}
+
+fn create_world_gen(
+    mut commands: Commands,
+    world_gen_assets: Res<WorldGenAssets>,
+    configs: Res<Assets<WorldGenConfig>>,
+    biome_defs: Res<Assets<BiomeDef>>,
+    obstacle_defs: Res<Assets<ObstacleDef>>,
+    deposit_defs: Res<Assets<DepositDef>>,
+    town_defs: Res<Assets<TownDef>>,
+    mut rng: ResMut<Seed>,
+) {
+    let config = configs
+        .get(&world_gen_assets.config)
+        .cloned()
+        .unwrap_or_default();
+
+    let spec = WorldSpec::new(
+        biome_defs
+            .iter()
+            .map(|(_, def)| BiomeSpec {
+                id: def.id.clone(),
+                climate: def.climate,
+            })
+            .collect(),
+        obstacle_defs
+            .iter()
+            .map(|(_, def)| ObstacleSpec {
+                id: def.id.clone(),
+                biomes: def.biomes.clone(),
+            })
+            .collect(),
+        deposit_defs
+            .iter()
+            .map(|(_, def)| DepositSpec {
+                id: def.id.clone(),
+                generation: def.generation.clone(),
+            })
+            .collect(),
+        town_defs
+            .iter()
+            .map(|(_, def)| TownSpec {
+                id: def.id.clone(),
+                population: def.population,
+            })
+            .collect(),
+    );
+
+    let seed = config.seed.unwrap_or_else(|| rng.random());
+
+    commands.insert_resource(WorldGen::new(config, seed, spec));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gameplay::structure::deposit::Cluster;
+
+    use super::*;
+
+    fn spec() -> WorldSpec {
+        WorldSpec::new(
+            vec![
+                BiomeSpec {
+                    id: "grassland".into(),
+                    climate: Vec2::ZERO,
+                },
+                BiomeSpec {
+                    id: "desert".into(),
+                    climate: Vec2::new(0.5, -0.5),
+                },
+            ],
+            vec![ObstacleSpec {
+                id: "boulder".into(),
+                biomes: [("desert".to_string(), 0.1)].into(),
+            }],
+            vec![DepositSpec {
+                id: "flora".into(),
+                generation: DepositGeneration {
+                    seed: 7,
+                    biomes: Default::default(),
+                    threshold: 0.2,
+                    noise_scale: 0.1,
+                    cluster: Cluster::Solid,
+                    richness: [10, 20],
+                    riverbank: false,
+                },
+            }],
+            vec![TownSpec {
+                id: "hamlet".into(),
+                population: [4, 8],
+            }],
+        )
+    }
+
+    #[test]
+    fn test_pipeline_is_deterministic_per_seed() {
+        let config = WorldGenConfig::default();
+        let plan = |seed: u32| Pipeline::new(&config, seed, &spec()).generate(IVec2::new(-2, 3));
+
+        assert_eq!(plan(42), plan(42));
+        assert_ne!(plan(42), plan(43));
+
+        let plan = plan(42);
+        assert!(plan.iter().all(|(_, _, tile)| tile.biome.is_some()));
+        assert!(
+            plan.iter().any(|(_, _, tile)| matches!(
+                tile.construct,
+                Some(PlannedConstruct::Deposit { .. })
+            ))
+        );
+    }
+
+    #[test]
+    fn test_chunk_plan_covers_its_tiles() {
+        let plan = ChunkPlan::new(IVec2::new(-1, 2), UVec2::new(4, 2));
+        let coords: Vec<IVec2> = plan.iter().map(|(_, coord, _)| coord).collect();
+
+        assert_eq!(coords.len(), 8);
+        assert_eq!(coords[0], IVec2::new(-4, 4));
+        assert_eq!(coords[7], IVec2::new(-1, 5));
+        assert!(plan.iter().all(|(_, _, tile)| *tile == TilePlan::default()));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::gameplay::world::{
+    generation::{ChunkGenerator, ChunkPlan, PlannedConstruct, Terrain, WorldSpec},
+    tilemap::coord::tile_hash,
+};
+
+/// Salt of the tile hash deciding where obstacles stand
+const OBSTACLE_SALT: u32 = 0x0B57_AC1E;
+
+/// Scatters obstacles over tiles left empty, as densely as each biome calls for
+pub struct ObstacleGenerator {
+    salt: u32,
+    /// Density of each obstacle, by obstacle then by biome index
+    densities: Vec<Vec<f32>>,
+}
+
+impl ObstacleGenerator {
+    pub fn new(seed: u32, spec: &WorldSpec) -> Self {
+        Self {
+            salt: seed ^ OBSTACLE_SALT,
+            densities: spec
+                .obstacles
+                .iter()
+                .map(|obstacle| {
+                    spec.biomes
+                        .iter()
+                        .map(|biome| obstacle.biomes.get(&biome.id).copied().unwrap_or(0.0))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ChunkGenerator for ObstacleGenerator {
+    fn generate(&self, plan: &mut ChunkPlan) {
+        for (coord, tile) in plan.iter_mut() {
+            let Some(biome) = tile.biome else {
+                continue;
+            };
+
+            if tile.construct.is_some() || tile.terrain == Terrain::River {
+                continue;
+            }
+
+            let roll = tile_hash(coord, self.salt) as f32 / u32::MAX as f32;
+            let densities = self
+                .densities
+                .iter()
+                .enumerate()
+                .map(|(obstacle, densities)| (densities[biome], obstacle));
+
+            tile.construct = pick_obstacle(roll, densities).map(PlannedConstruct::Obstacle);
+        }
+    }
+}
+
+/// Obstacle standing on a tile, given a roll between 0 and 1 and the density of each obstacle in
+/// the tile's biome. Densities are stacked in the order given, so the same roll always picks the
+/// same obstacle for the same list.
+fn pick_obstacle<T>(roll: f32, densities: impl IntoIterator<Item = (f32, T)>) -> Option<T> {
+    let mut stacked = 0.0;
+
+    densities.into_iter().find_map(|(density, obstacle)| {
+        stacked += density;
+        (roll < stacked).then_some(obstacle)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_obstacle_stacks_densities() {
+        let densities = [(0.1, "boulder"), (0.2, "dead_tree")];
+
+        assert_eq!(pick_obstacle(0.05, densities), Some("boulder"));
+        assert_eq!(pick_obstacle(0.25, densities), Some("dead_tree"));
+        assert_eq!(pick_obstacle(0.3, densities), None);
+    }
+}
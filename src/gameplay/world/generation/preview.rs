@@ -0,0 +1,336 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::gameplay::{
+    structure::{deposit::DepositRaw, obstacle::ObstacleRaw, town::TownRaw},
+    world::{
+        biome::BiomeRaw,
+        generation::{
+            BiomeSpec, DepositSpec, ObstacleSpec, PlannedConstruct, Terrain, TilePlan, TownSpec,
+            WorldGen, WorldGenConfig, WorldGenRaw, WorldSpec,
+        },
+    },
+};
+
+/// Symbols obstacles are drawn with, in the order of the world spec
+const OBSTACLE_SYMBOLS: &[char] = &['#', '^', '%', '&', '*', '@', '+', '='];
+
+const RIVER_SYMBOL: char = '~';
+
+/// Symbols towns are drawn with, in the order of the world spec
+const TOWN_SYMBOLS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+#[derive(Debug, Error)]
+pub enum PreviewError {
+    #[error("Could not read manifest: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse manifest: {0}")]
+    Deserialize(#[from] toml::de::Error),
+}
+
+/// Reads the generation config and the world spec straight from a manifest folder, without
+/// going through the asset server
+pub fn load_manifests(manifests: &Path) -> Result<(WorldGenConfig, WorldSpec), PreviewError> {
+    let config: WorldGenRaw =
+        toml::from_str(&fs::read_to_string(manifests.join("worldgen.toml"))?)?;
+
+    let biomes = read_manifests::<BiomeRaw>(&manifests.join("biomes"), "biome.toml")?
+        .into_iter()
+        .map(|raw| BiomeSpec {
+            id: raw.id,
+            climate: Vec2::from(raw.climate),
+        })
+        .collect();
+
+    let obstacles = read_manifests::<ObstacleRaw>(&manifests.join("obstacles"), "obstacle.toml")?
+        .into_iter()
+        .map(|raw| ObstacleSpec {
+            id: raw.id,
+            biomes: raw.biomes,
+        })
+        .collect();
+
+    let deposits = read_manifests::<DepositRaw>(&manifests.join("deposits"), "deposit.toml")?
+        .into_iter()
+        .map(|raw| DepositSpec {
+            id: raw.id,
+            generation: raw.generation,
+        })
+        .collect();
+
+    let towns = read_manifests::<TownRaw>(&manifests.join("towns"), "town.toml")?
+        .into_iter()
+        .map(|raw| TownSpec {
+            id: raw.id,
+            population: raw.population,
+        })
+        .collect();
+
+    Ok((
+        config.into(),
+        WorldSpec::new(biomes, obstacles, deposits, towns),
+    ))
+}
+
+fn read_manifests<T: DeserializeOwned>(
+    folder: &Path,
+    extension: &str,
+) -> Result<Vec<T>, PreviewError> {
+    let paths: Vec<PathBuf> = fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.to_string_lossy().ends_with(extension))
+        .collect();
+
+    paths
+        .iter()
+        .map(|path| Ok(toml::from_str(&fs::read_to_string(path)?)?))
+        .collect()
+}
+
+/// Generates the chunks of the region, inclusive of both corners, and renders them as text
+pub fn preview(manifests: &Path, seed: Option<u32>, chunks: IRect) -> Result<String, PreviewError> {
+    let (config, spec) = load_manifests(manifests)?;
+    let seed = seed.or(config.seed).unwrap_or_default();
+    let world_gen = WorldGen::new(config, seed, spec);
+
+    Ok(Preview::generate(&world_gen, chunks).render(&world_gen.spec))
+}
+
+/// Generated tiles of a rectangular region
+pub struct Preview {
+    origin: IVec2,
+    size: UVec2,
+    tiles: Vec<TilePlan>,
+}
+
+impl Preview {
+    /// Runs the pipeline on every chunk of the region, inclusive of both corners
+    pub fn generate(world_gen: &WorldGen, chunks: IRect) -> Self {
+        let chunk_size = world_gen.config.chunk_size.as_ivec2();
+        let origin = chunks.min * chunk_size;
+        let size = ((chunks.size() + IVec2::ONE) * chunk_size).as_uvec2();
+        let mut tiles = vec![TilePlan::default(); (size.x * size.y) as usize];
+
+        for y in chunks.min.y..=chunks.max.y {
+            for x in chunks.min.x..=chunks.max.x {
+                let plan = world_gen.pipeline.generate(IVec2::new(x, y));
+
+                for (_, coord, tile) in plan.iter() {
+                    let local = (coord - origin).as_uvec2();
+                    tiles[(local.y * size.x + local.x) as usize] = tile.clone();
+                }
+            }
+        }
+
+        Self {
+            origin,
+            size,
+            tiles,
+        }
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = (IVec2, &TilePlan)> {
+        let width = self.size.x;
+
+        self.tiles.iter().enumerate().map(move |(index, tile)| {
+            let local = UVec2::new(index as u32 % width, index as u32 / width);
+            (self.origin + local.as_ivec2(), tile)
+        })
+    }
+
+    /// Tiles and total items of each deposit in the region, by id
+    pub fn deposit_counts<'a>(&self, spec: &'a WorldSpec) -> BTreeMap<&'a str, (u32, u32)> {
+        let mut counts: BTreeMap<&str, (u32, u32)> = spec
+            .deposits
+            .iter()
+            .map(|deposit| (deposit.id.as_str(), (0, 0)))
+            .collect();
+
+        for (_, tile) in self.tiles() {
+            let Some(PlannedConstruct::Deposit { deposit, quantity }) = tile.construct else {
+                continue;
+            };
+
+            let (tiles, items) = counts
+                .entry(spec.deposits[deposit].id.as_str())
+                .or_default();
+            *tiles += 1;
+            *items += quantity;
+        }
+
+        counts
+    }
+
+    /// Map of the region with one character per tile, the highest row first, followed by its
+    /// legend and the deposit counts
+    pub fn render(&self, spec: &WorldSpec) -> String {
+        let legend = Legend::new(spec);
+        let mut output = String::new();
+
+        for y in (0..self.size.y).rev() {
+            let row = &self.tiles[(y * self.size.x) as usize..((y + 1) * self.size.x) as usize];
+            output.extend(row.iter().map(|tile| legend.symbol(tile)));
+            output.push('\n');
+        }
+
+        let max = self.origin + self.size.as_ivec2() - IVec2::ONE;
+        let _ = writeln!(output, "\nTiles {} to {}", self.origin, max);
+
+        for (symbol, kind, id) in legend.entries(spec) {
+            let _ = writeln!(output, "{symbol} {kind} {id}");
+        }
+
+        output.push('\n');
+        for (id, (tiles, items)) in self.deposit_counts(spec) {
+            let _ = writeln!(output, "{id}: {tiles} tiles, {items} items");
+        }
+
+        output
+    }
+}
+
+/// Characters each biome, obstacle and deposit of a world spec is drawn with
+struct Legend {
+    biomes: Vec<char>,
+    obstacles: Vec<char>,
+    deposits: Vec<char>,
+    towns: Vec<char>,
+}
+
+impl Legend {
+    fn new(spec: &WorldSpec) -> Self {
+        let mut taken = HashSet::new();
+
+        Self {
+            biomes: spec
+                .biomes
+                .iter()
+                .map(|biome| unique_symbol(&biome.id.to_ascii_lowercase(), &mut taken))
+                .collect(),
+            obstacles: (0..spec.obstacles.len())
+                .map(|index| OBSTACLE_SYMBOLS.get(index).copied().unwrap_or('?'))
+                .collect(),
+            deposits: spec
+                .deposits
+                .iter()
+                .map(|deposit| unique_symbol(&deposit.id.to_ascii_uppercase(), &mut taken))
+                .collect(),
+            towns: (0..spec.towns.len())
+                .map(|index| TOWN_SYMBOLS.get(index).copied().unwrap_or('?'))
+                .collect(),
+        }
+    }
+
+    fn symbol(&self, tile: &TilePlan) -> char {
+        match tile.construct {
+            Some(PlannedConstruct::Obstacle(obstacle)) => self.obstacles[obstacle],
+            Some(PlannedConstruct::Deposit { deposit, .. }) => self.deposits[deposit],
+            Some(PlannedConstruct::Town { town, .. }) => self.towns[town],
+            None if tile.terrain == Terrain::River => RIVER_SYMBOL,
+            None => tile.biome.map_or(' ', |biome| self.biomes[biome]),
+        }
+    }
+
+    fn entries<'a>(
+        &'a self,
+        spec: &'a WorldSpec,
+    ) -> impl Iterator<Item = (char, &'a str, &'a str)> {
+        let biomes = self
+            .biomes
+            .iter()
+            .zip(&spec.biomes)
+            .map(|(symbol, biome)| (*symbol, "biome", biome.id.as_str()));
+        let obstacles = self
+            .obstacles
+            .iter()
+            .zip(&spec.obstacles)
+            .map(|(symbol, obstacle)| (*symbol, "obstacle", obstacle.id.as_str()));
+        let deposits = self
+            .deposits
+            .iter()
+            .zip(&spec.deposits)
+            .map(|(symbol, deposit)| (*symbol, "deposit", deposit.id.as_str()));
+        let towns = self
+            .towns
+            .iter()
+            .zip(&spec.towns)
+            .map(|(symbol, town)| (*symbol, "town", town.id.as_str()));
+
+        biomes
+            .chain([(RIVER_SYMBOL, "terrain", "river")])
+            .chain(obstacles)
+            .chain(deposits)
+            .chain(towns)
+    }
+}
+
+/// First letter of the id not already used by another entry
+fn unique_symbol(id: &str, taken: &mut HashSet<char>) -> char {
+    let symbol = id
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .find(|symbol| !taken.contains(symbol))
+        .unwrap_or('?');
+
+    taken.insert(symbol);
+    symbol
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render of the game manifests for seed 7 over the four chunks around the origin. Regenerate
+    /// it when generation or the manifests change on purpose, with
+    /// `cargo run --bin worldgen_preview -- --seed 7 --from -1,-1 --to 0,0 > preview_seed_7.txt`
+    const SEED_7_RENDER: &str = include_str!("preview_seed_7.txt");
+
+    #[test]
+    fn test_preview_of_game_manifests_is_stable() {
+        let manifests = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/manifests");
+        let chunks = IRect::new(-1, -1, 0, 0);
+
+        let (config, spec) = load_manifests(&manifests).unwrap();
+        let world_gen = WorldGen::new(config, 7, spec);
+        let generated = Preview::generate(&world_gen, chunks);
+        let render = generated.render(&world_gen.spec);
+
+        assert_eq!(render, SEED_7_RENDER);
+        assert_eq!(preview(&manifests, Some(7), chunks).unwrap(), SEED_7_RENDER);
+
+        let rows: Vec<&str> = render.lines().take_while(|row| !row.is_empty()).collect();
+        let size = world_gen.config.chunk_size * 2;
+        assert_eq!(rows.len(), size.y as usize);
+        assert!(
+            rows.iter()
+                .all(|row| row.chars().count() == size.x as usize)
+        );
+
+        let counts = generated.deposit_counts(&world_gen.spec);
+        let legend = Legend::new(&world_gen.spec);
+        for (deposit, symbol) in world_gen.spec.deposits.iter().zip(legend.deposits) {
+            let drawn = rows
+                .iter()
+                .flat_map(|row| row.chars())
+                .filter(|c| *c == symbol);
+            assert_eq!(counts[deposit.id.as_str()].0 as usize, drawn.count());
+        }
+    }
+
+    #[test]
+    fn test_unique_symbol_skips_taken_letters() {
+        let mut taken = HashSet::new();
+
+        assert_eq!(unique_symbol("forest", &mut taken), 'f');
+        assert_eq!(unique_symbol("flora", &mut taken), 'l');
+        assert_eq!(unique_symbol("f", &mut taken), '?');
+    }
+}
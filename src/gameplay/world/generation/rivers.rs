@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use noise::{NoiseFn, OpenSimplex};
+
+use crate::gameplay::world::{
+    generation::{ChunkGenerator, ChunkPlan, Terrain, WorldGenConfig},
+    tilemap::CARDINALS,
+};
+
+const RIVER_SALT: u32 = 740_129;
+
+/// Noise whose zero crossings trace rivers. Being sampled per tile rather than per chunk, rivers
+/// wind across chunk borders without seams.
+#[derive(Clone)]
+pub struct RiverMap {
+    noise: OpenSimplex,
+    scale: f64,
+    width: f64,
+}
+
+impl RiverMap {
+    pub fn new(seed: u32, config: &WorldGenConfig) -> Self {
+        Self {
+            noise: OpenSimplex::new(seed ^ RIVER_SALT),
+            scale: config.river_scale,
+            width: config.river_width,
+        }
+    }
+
+    pub fn is_river(&self, coord: IVec2) -> bool {
+        let point: [f64; 2] = (coord.as_dvec2() * self.scale).into();
+        self.noise.get(point).abs() < self.width
+    }
+
+    pub fn terrain(&self, coord: IVec2) -> Terrain {
+        if self.is_river(coord) {
+            Terrain::River
+        } else if CARDINALS
+            .iter()
+            .any(|direction| self.is_river(coord + direction))
+        {
+            Terrain::Riverbank
+        } else {
+            Terrain::Land
+        }
+    }
+}
+
+/// Cuts rivers through the land, marking the tiles along them as riverbanks
+pub struct RiverGenerator {
+    map: RiverMap,
+}
+
+impl RiverGenerator {
+    pub fn new(seed: u32, config: &WorldGenConfig) -> Self {
+        Self {
+            map: RiverMap::new(seed, config),
+        }
+    }
+}
+
+impl ChunkGenerator for RiverGenerator {
+    fn generate(&self, plan: &mut ChunkPlan) {
+        for (coord, tile) in plan.iter_mut() {
+            tile.terrain = self.map.terrain(coord);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_rivers_continue_across_chunks() {
+        let config = WorldGenConfig::default();
+        let generator = RiverGenerator::new(11, &config);
+
+        let mut terrain = HashMap::new();
+        for y in -2..2 {
+            for x in -2..2 {
+                let mut plan = ChunkPlan::new(IVec2::new(x, y), config.chunk_size);
+                generator.generate(&mut plan);
+                terrain.extend(plan.iter().map(|(_, coord, tile)| (coord, tile.terrain)));
+            }
+        }
+
+        assert!(terrain.values().any(|terrain| *terrain == Terrain::River));
+
+        // Rivers are always lined by banks, including where they cross into the next chunk
+        for (coord, tile) in terrain.iter() {
+            if *tile != Terrain::River {
+                continue;
+            }
+
+            for direction in CARDINALS {
+                assert_ne!(terrain.get(&(coord + direction)), Some(&Terrain::Land));
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::gameplay::world::{
+    generation::{
+        ChunkGenerator, ChunkPlan, PlannedConstruct, Stage, Terrain, WorldGenConfig, WorldSpec,
+        rivers::RiverMap,
+    },
+    tilemap::coord::tile_hash,
+};
+
+const TOWN_SALT: u32 = 918_273;
+
+/// Spots within its cell tried for a town, in case the first ones are underwater
+const SITE_ATTEMPTS: u32 = 4;
+
+/// Founds towns on a grid of cells, at most one per cell and always one around the origin
+pub struct TownGenerator {
+    salt: u32,
+    spacing: i32,
+    chance: f32,
+    /// Smallest and largest population of each town, by town index
+    populations: Vec<[u32; 2]>,
+    river_map: Option<RiverMap>,
+}
+
+impl TownGenerator {
+    pub fn new(seed: u32, config: &WorldGenConfig, spec: &WorldSpec) -> Self {
+        Self {
+            salt: seed ^ TOWN_SALT,
+            spacing: config.town_spacing.max(1) as i32,
+            chance: config.town_chance,
+            populations: spec.towns.iter().map(|town| town.population).collect(),
+            river_map: config
+                .stages
+                .contains(&Stage::Rivers)
+                .then(|| RiverMap::new(seed, config)),
+        }
+    }
+
+    /// How far a town may stand from the centre of its cell in each direction
+    fn jitter(&self) -> i32 {
+        self.spacing / 8
+    }
+
+    fn hash(&self, cell: IVec2, salt: u32) -> u32 {
+        tile_hash(cell, self.salt ^ salt)
+    }
+
+    /// Cell of the town grid containing the coordinate, cell zero being centred on the origin
+    pub fn cell(&self, coord: IVec2) -> IVec2 {
+        (coord + IVec2::splat(self.spacing / 2)).div_euclid(IVec2::splat(self.spacing))
+    }
+
+    fn is_river(&self, coord: IVec2) -> bool {
+        self.river_map
+            .as_ref()
+            .is_some_and(|river_map| river_map.is_river(coord))
+    }
+
+    /// Tile the town of the cell stands on, if the cell has one. The cell around the origin
+    /// always does, so there's a town to recruit from right away. Should every spot tried be
+    /// underwater, the town stands on the land nearest the centre of the cell instead.
+    pub fn site(&self, cell: IVec2) -> Option<IVec2> {
+        if self.populations.is_empty() {
+            return None;
+        }
+
+        let roll = self.hash(cell, 0) as f32 / u32::MAX as f32;
+        if cell != IVec2::ZERO && roll >= self.chance {
+            return None;
+        }
+
+        let jitter = self.jitter();
+        let spread = (jitter * 2 + 1) as u32;
+
+        (1..=SITE_ATTEMPTS)
+            .map(|attempt| {
+                let offset = IVec2::new(
+                    (self.hash(cell, attempt * 2) % spread) as i32 - jitter,
+                    (self.hash(cell, attempt * 2 + 1) % spread) as i32 - jitter,
+                );
+
+                cell * self.spacing + offset
+            })
+            .find(|site| !self.is_river(*site))
+            .or_else(|| self.nearest_land(cell))
+    }
+
+    /// Land tile of the cell nearest its centre, searched ring by ring
+    fn nearest_land(&self, cell: IVec2) -> Option<IVec2> {
+        let centre = cell * self.spacing;
+
+        // Kept inside the cell, which spans half the spacing either side of its centre
+        (0..=(self.spacing - 1) / 2).find_map(|radius| {
+            (-radius..=radius)
+                .flat_map(|y| (-radius..=radius).map(move |x| IVec2::new(x, y)))
+                .filter(|offset| offset.abs().max_element() == radius)
+                .map(|offset| centre + offset)
+                .filter(|tile| !self.is_river(*tile))
+                .min_by_key(|tile| ((*tile - centre).length_squared(), tile.y, tile.x))
+        })
+    }
+}
+
+impl ChunkGenerator for TownGenerator {
+    fn generate(&self, plan: &mut ChunkPlan) {
+        // Sites of the cells the chunk overlaps, worked out once for each cell
+        let mut sites: HashMap<IVec2, Option<IVec2>> = HashMap::new();
+
+        for (coord, tile) in plan.iter_mut() {
+            if tile.construct.is_some() || tile.terrain == Terrain::River || tile.biome.is_none() {
+                continue;
+            }
+
+            let cell = self.cell(coord);
+
+            if *sites.entry(cell).or_insert_with(|| self.site(cell)) != Some(coord) {
+                continue;
+            }
+
+            let town = self.hash(cell, SITE_ATTEMPTS * 2 + 2) as usize % self.populations.len();
+            let [min, max] = self.populations[town];
+            let population =
+                min + self.hash(cell, SITE_ATTEMPTS * 2 + 3) % (max.saturating_sub(min) + 1);
+
+            tile.construct = Some(PlannedConstruct::Town { town, population });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::gameplay::world::{fog::STARTING_REVEAL, generation::TownSpec};
+
+    use super::*;
+
+    #[test]
+    fn test_town_founded_near_origin() {
+        let config = WorldGenConfig::default();
+        let spec = WorldSpec {
+            towns: vec![TownSpec {
+                id: "hamlet".into(),
+                population: [4, 8],
+            }],
+            ..default()
+        };
+
+        for seed in 0..20 {
+            let generator = TownGenerator::new(seed, &config, &spec);
+            let site = generator.site(IVec2::ZERO).unwrap();
+
+            assert!(site.abs().element_sum() <= STARTING_REVEAL);
+            assert_eq!(generator.cell(site), IVec2::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_town_founded_on_land_despite_rivers() {
+        // Wide rivers, so every spot tried for some of the towns lies underwater
+        let config = WorldGenConfig {
+            river_width: 0.15,
+            stages: vec![Stage::Biomes, Stage::Rivers, Stage::Towns],
+            ..default()
+        };
+        let spec = WorldSpec {
+            towns: vec![TownSpec {
+                id: "hamlet".into(),
+                population: [4, 8],
+            }],
+            ..default()
+        };
+
+        for seed in 0..20 {
+            let generator = TownGenerator::new(seed, &config, &spec);
+            let river_map = RiverMap::new(seed, &config);
+            let site = generator.site(IVec2::ZERO).unwrap();
+
+            assert!(!river_map.is_river(site));
+            assert_eq!(generator.cell(site), IVec2::ZERO);
+        }
+    }
+}
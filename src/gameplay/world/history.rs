@@ -0,0 +1,514 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    gameplay::{
+        hud::hotbar::HotbarActionKind,
+        inventory::prelude::*,
+        player::Player,
+        recipe::{
+            assets::Recipe,
+            select::{ClearRecipe, SelectRecipe},
+        },
+        structure::{
+            Structure,
+            assets::StructureDef,
+            orientation::Orientation,
+            path::{PathDef, PathSegment, path_segment},
+            placement_rule::PlacementFailure,
+        },
+        world::{
+            construction::{
+                ConstructKind, ConstructReplaced, Constructions, PlacementSurroundings,
+                StructureConstructed, placement_failures, spawn_structure,
+            },
+            construction_site::{
+                ConstructionSite, DeliveredMaterials, SiteSlot, construction_site,
+            },
+            demolition::Demolished,
+            tilemap::coord::Coord,
+        },
+    },
+    input::input_map::{Action, action_just_pressed},
+};
+
+/// Steps kept for undoing unless configured otherwise
+pub const DEFAULT_HISTORY_DEPTH: usize = 50;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<History>();
+
+    app.add_systems(
+        Update,
+        (
+            undo.run_if(action_just_pressed(Action::Undo)),
+            redo.run_if(action_just_pressed(Action::Redo)),
+        ),
+    );
+}
+
+/// Construct as it was placed or removed, enough to place it again
+#[derive(Reflect, Debug, Clone)]
+pub struct PlacedConstruct {
+    pub coord: IVec2,
+    pub construct: ConstructKind,
+    pub orientation: Orientation,
+    pub recipe: Option<AssetId<Recipe>>,
+}
+
+impl PlacedConstruct {
+    pub fn new(coord: IVec2, construct: ConstructKind) -> Self {
+        Self {
+            coord,
+            construct,
+            orientation: Orientation::default(),
+            recipe: None,
+        }
+    }
+}
+
+/// Player action that can be undone and redone
+#[derive(Reflect, Debug, Clone)]
+pub enum HistoryStep {
+    /// Constructs placed and removed together, such as a junction replacing a path segment
+    Constructs {
+        placed: Vec<PlacedConstruct>,
+        removed: Vec<PlacedConstruct>,
+    },
+    /// Recipe selected on the structure at the coordinate
+    Recipe {
+        coord: IVec2,
+        from: Option<AssetId<Recipe>>,
+        to: Option<AssetId<Recipe>>,
+    },
+}
+
+impl HistoryStep {
+    /// Step reverting this one
+    pub fn inverse(&self) -> Self {
+        match self {
+            HistoryStep::Constructs { placed, removed } => HistoryStep::Constructs {
+                placed: removed.clone(),
+                removed: placed.clone(),
+            },
+            HistoryStep::Recipe { coord, from, to } => HistoryStep::Recipe {
+                coord: *coord,
+                from: *to,
+                to: *from,
+            },
+        }
+    }
+}
+
+/// Player actions that can be undone, most recent last
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct History {
+    /// Number of steps kept for undoing, the oldest being forgotten past it
+    pub depth: usize,
+    undo: VecDeque<HistoryStep>,
+    redo: Vec<HistoryStep>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self {
+            depth: DEFAULT_HISTORY_DEPTH,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl History {
+    /// Records a new action, which can no longer be followed by redoing previously undone ones
+    pub fn record(&mut self, step: HistoryStep) {
+        self.redo.clear();
+        self.push_undo(step);
+    }
+
+    fn push_undo(&mut self, step: HistoryStep) {
+        self.undo.push_back(step);
+
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+}
+
+/// Access to everything needed to place and remove constructs when stepping through history
+#[derive(SystemParam)]
+pub struct HistoryWorld<'w, 's> {
+    commands: Commands<'w, 's>,
+    constructions: ResMut<'w, Constructions>,
+    constructs: Query<
+        'w,
+        's,
+        (
+            &'static Coord,
+            Option<&'static Structure>,
+            Option<&'static PathSegment>,
+            Option<&'static ConstructionSite>,
+        ),
+    >,
+    site_slots: Query<'w, 's, (), With<SiteSlot>>,
+    path_segments: Query<'w, 's, &'static PathSegment>,
+    surroundings: PlacementSurroundings<'w, 's>,
+    player: Single<'w, 's, Entity, With<Player>>,
+    inventory: Query<'w, 's, &'static Inventory>,
+    stacks: Query<'w, 's, &'static mut ItemStack>,
+    structure_defs: Res<'w, Assets<StructureDef>>,
+    path_defs: Res<'w, Assets<PathDef>>,
+    asset_server: Res<'w, AssetServer>,
+    structures_constructed: MessageWriter<'w, StructureConstructed>,
+    demolitions: MessageWriter<'w, Demolished>,
+}
+
+impl HistoryWorld<'_, '_> {
+    /// Applies the step if everything it places fits and can be afforded
+    pub fn apply(&mut self, step: &HistoryStep) -> bool {
+        match step {
+            HistoryStep::Constructs { placed, removed } => {
+                let removing: Vec<Entity> = removed
+                    .iter()
+                    .filter_map(|construct| self.find(construct))
+                    .collect();
+
+                // Something removed since, leaving the step nothing to revert
+                if removing.len() != removed.len()
+                    || !self.fits(placed, &removing)
+                    || !self.affordable(placed)
+                {
+                    return false;
+                }
+
+                // Paths given up for whatever is placed on their tile, such as a junction
+                let mut replaced_paths = HashMap::new();
+
+                for entity in removing {
+                    if let Ok((coord, _, Some(_), _)) = self.constructs.get(entity) {
+                        replaced_paths.insert(coord.0, entity);
+                    }
+
+                    self.remove(entity);
+                }
+
+                for construct in placed {
+                    let Some(replacement) = self.place(construct) else {
+                        continue;
+                    };
+
+                    if let Some(replaced) = replaced_paths.remove(&construct.coord) {
+                        self.commands.trigger(ConstructReplaced {
+                            replaced,
+                            replacement,
+                        });
+                    }
+                }
+
+                true
+            }
+            HistoryStep::Recipe { coord, to, .. } => {
+                let Some(&entity) = self.constructions.get(coord) else {
+                    return false;
+                };
+
+                match to {
+                    Some(recipe) => self.commands.trigger(SelectRecipe {
+                        entity,
+                        recipe: *recipe,
+                    }),
+                    None => self.commands.trigger(ClearRecipe { entity }),
+                }
+
+                true
+            }
+        }
+    }
+
+    /// Entity still standing where the construct was recorded, if it wasn't replaced since. Sites
+    /// that have been built since are found as their finished structure.
+    fn find(&self, construct: &PlacedConstruct) -> Option<Entity> {
+        let entity = *self.constructions.get(&construct.coord)?;
+        let (coord, structure, path_segment, site) = self.constructs.get(entity).ok()?;
+
+        let matches = match &construct.construct {
+            ConstructKind::Structure(handle) => structure.is_some_and(|s| s.0 == *handle),
+            ConstructKind::Path(handle) => path_segment.is_some_and(|p| p.0 == *handle),
+            ConstructKind::Site {
+                structure: handle, ..
+            } => {
+                site.is_some_and(|site| site.structure == *handle)
+                    || structure.is_some_and(|s| s.0 == *handle)
+            }
+        };
+
+        (matches && coord.0 == construct.coord).then_some(entity)
+    }
+
+    fn tiles(&self, construct: &PlacedConstruct) -> Vec<IVec2> {
+        let handle = match &construct.construct {
+            ConstructKind::Structure(handle)
+            | ConstructKind::Site {
+                structure: handle, ..
+            } => handle,
+            ConstructKind::Path(_) => return vec![construct.coord],
+        };
+
+        self.structure_defs
+            .get(handle)
+            .map(|def| {
+                construct
+                    .orientation
+                    .footprint(&def.footprint)
+                    .tiles(construct.coord)
+                    .collect()
+            })
+            .unwrap_or_else(|| vec![construct.coord])
+    }
+
+    /// Whether the constructs can be placed once the removed ones are gone. Their tiles may only
+    /// overlap what is being removed, and every other check of a regular placement must pass.
+    fn fits(&self, placed: &[PlacedConstruct], removing: &[Entity]) -> bool {
+        let mut covered = HashSet::new();
+
+        placed.iter().all(|construct| {
+            let free = self.tiles(construct).into_iter().all(|tile| {
+                covered.insert(tile)
+                    && self
+                        .constructions
+                        .get(&tile)
+                        .is_none_or(|entity| removing.contains(entity))
+            });
+
+            free && self
+                .placement_failures(construct)
+                .iter()
+                .all(|failure| *failure == PlacementFailure::Occupied)
+        })
+    }
+
+    fn placement_failures(&self, construct: &PlacedConstruct) -> Vec<PlacementFailure> {
+        let action = match &construct.construct {
+            ConstructKind::Structure(handle)
+            | ConstructKind::Site {
+                structure: handle, ..
+            } => HotbarActionKind::PlaceStructure(handle.clone()),
+            ConstructKind::Path(handle) => HotbarActionKind::PlacePath(handle.clone()),
+        };
+
+        placement_failures(
+            &action,
+            &construct.coord,
+            &construct.orientation,
+            &self.structure_defs,
+            &self.constructions,
+            &self.path_segments,
+            &self.surroundings,
+        )
+    }
+
+    /// Cost paid up front for placing the constructs. Sites only take back what had been delivered
+    /// to them, which was refunded when they were removed.
+    fn cost(&self, placed: &[PlacedConstruct]) -> HashMap<Handle<ItemDef>, u32> {
+        let mut total = HashMap::new();
+
+        for construct in placed {
+            let cost = match &construct.construct {
+                ConstructKind::Structure(handle) => {
+                    self.structure_defs.get(handle).map(|def| &def.cost)
+                }
+                ConstructKind::Path(handle) => self.path_defs.get(handle).map(|def| &def.cost),
+                ConstructKind::Site { delivered, .. } => Some(delivered),
+            };
+
+            for (item, quantity) in cost.into_iter().flatten() {
+                *total.entry(item.clone()).or_default() += quantity;
+            }
+        }
+
+        total
+    }
+
+    fn affordable(&self, placed: &[PlacedConstruct]) -> bool {
+        can_afford(
+            *self.player,
+            &self.cost(placed),
+            &self.inventory,
+            &self.stacks,
+        )
+    }
+
+    /// Removes the construct like a demolition, refunding it the same way
+    fn remove(&mut self, entity: Entity) {
+        let Ok((coord, structure, path_segment, site)) = self.constructs.get(entity) else {
+            return;
+        };
+
+        let construct = match (structure, path_segment, site) {
+            (Some(structure), _, _) => ConstructKind::Structure(structure.0.clone()),
+            (None, Some(path_segment), _) => ConstructKind::Path(path_segment.0.clone()),
+            (None, None, Some(site)) => ConstructKind::Site {
+                structure: site.structure.clone(),
+                delivered: self
+                    .inventory
+                    .iter_descendants(entity)
+                    .filter(|slot| self.site_slots.contains(*slot))
+                    .filter_map(|slot| self.stacks.get(slot).ok())
+                    .filter(|stack| stack.quantity > 0)
+                    .map(|stack| (stack.item.clone(), stack.quantity))
+                    .collect(),
+            },
+            (None, None, None) => return,
+        };
+
+        let coord = Coord(coord.0);
+
+        self.commands.entity(entity).despawn();
+
+        // Freed right away so constructs replacing it in the same step can be placed
+        self.constructions
+            .retain(|_, construction| *construction != entity);
+
+        self.demolitions.write(Demolished {
+            entity,
+            construct,
+            coord,
+        });
+    }
+
+    fn place(&mut self, construct: &PlacedConstruct) -> Option<Entity> {
+        let entity = match &construct.construct {
+            ConstructKind::Structure(handle) => {
+                let def = self.structure_defs.get(handle)?;
+
+                spend(*self.player, &def.cost, &self.inventory, &mut self.stacks);
+
+                let entity = spawn_structure(
+                    &mut self.commands,
+                    def,
+                    handle.clone(),
+                    construct.coord,
+                    construct.orientation,
+                    &self.asset_server,
+                );
+
+                if let Some(recipe) = construct.recipe {
+                    self.commands.trigger(SelectRecipe { entity, recipe });
+                }
+
+                self.structures_constructed
+                    .write(StructureConstructed(entity));
+
+                entity
+            }
+            ConstructKind::Path(handle) => {
+                let def = self.path_defs.get(handle)?;
+
+                spend(*self.player, &def.cost, &self.inventory, &mut self.stacks);
+
+                let entity = self
+                    .commands
+                    .spawn(path_segment(
+                        def,
+                        handle.clone(),
+                        construct.coord,
+                        &self.asset_server,
+                    ))
+                    .id();
+
+                self.structures_constructed
+                    .write(StructureConstructed(entity));
+
+                entity
+            }
+            ConstructKind::Site {
+                structure,
+                delivered,
+            } => {
+                let def = self.structure_defs.get(structure)?;
+
+                spend(*self.player, delivered, &self.inventory, &mut self.stacks);
+
+                self.commands
+                    .spawn((
+                        construction_site(
+                            def,
+                            structure.clone(),
+                            construct.coord,
+                            construct.orientation,
+                            construct.recipe,
+                            &self.asset_server,
+                        ),
+                        DeliveredMaterials(delivered.clone()),
+                    ))
+                    .id()
+            }
+        };
+
+        for tile in self.tiles(construct) {
+            self.constructions.insert(tile, entity);
+        }
+
+        Some(entity)
+    }
+}
+
+fn undo(mut history: ResMut<History>, mut world: HistoryWorld) {
+    let Some(step) = history.undo.pop_back() else {
+        return;
+    };
+
+    if world.apply(&step.inverse()) {
+        history.redo.push(step);
+    } else {
+        history.undo.push_back(step);
+    }
+}
+
+fn redo(mut history: ResMut<History>, mut world: HistoryWorld) {
+    let Some(step) = history.redo.pop() else {
+        return;
+    };
+
+    if world.apply(&step) {
+        history.push_undo(step);
+    } else {
+        history.redo.push(step);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe_step(x: i32) -> HistoryStep {
+        HistoryStep::Recipe {
+            coord: IVec2::new(x, 0),
+            from: None,
+            to: None,
+        }
+    }
+
+    #[test]
+    fn test_history_forgets_steps_past_its_depth() {
+        let mut history = History {
+            depth: 2,
+            ..default()
+        };
+
+        history.redo.push(recipe_step(-1));
+
+        for x in 0..3 {
+            history.record(recipe_step(x));
+        }
+
+        assert!(history.redo.is_empty());
+        assert_eq!(history.undo.len(), 2);
+        assert!(matches!(
+            history.undo.front(),
+            Some(HistoryStep::Recipe { coord, .. }) if coord.x == 1
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    time::Duration,
+};
+
+use bevy::{picking::hover::PickingInteraction, prelude::*};
+
+use crate::{
+    gameplay::{
+        FactorySystems,
+        inventory::prelude::*,
+        people::{
+            porting::{
+                PorterArrival, PorterLost, Porting, Walkable, decrement_ttl, despawn_lost_porters,
+            },
+            profession::ProfessionSystems,
+            traffic::TileTraffic,
+        },
+        structure::{Structure, highlight::highlight_pickable, path::PathSegment},
+        world::{
+            construction::Constructions,
+            tilemap::{CARDINALS, coord::Coord},
+        },
+    },
+    input::input_map::{Action, action_just_pressed},
+};
+
+/// How far back porter activity is shown on the overlay
+pub const HEAT_WINDOW: Duration = Duration::from_secs(30);
+
+const LOST_PORTER_COLOR: Color = Color::hsl(300.0, 1.0, 0.5);
+const UNREACHABLE_COLOR: Color = Color::hsl(0.0, 0.0, 0.25);
+const DELIVERY_COLOR: Color = Color::hsl(120.0, 1.0, 0.5);
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LogisticsOverlay>();
+    app.init_resource::<LogisticsHeat>();
+
+    app.add_systems(
+        Update,
+        toggle_overlay.run_if(action_just_pressed(Action::LogisticsOverlay)),
+    );
+
+    app.add_systems(
+        FixedUpdate,
+        (
+            record_arrivals.in_set(ProfessionSystems),
+            record_losses
+                .in_set(ProfessionSystems)
+                .after(decrement_ttl)
+                .before(despawn_lost_porters),
+            expire_heat.after(ProfessionSystems),
+            tint_overlay
+                .run_if(overlay_enabled)
+                .after(highlight_pickable)
+                .before(FactorySystems::Demolish),
+        ),
+    );
+}
+
+/// Whether the logistics overlay is shown
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct LogisticsOverlay {
+    pub enabled: bool,
+}
+
+/// Recent porter activity, recorded with the time it happened
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct LogisticsHeat {
+    /// Structures that have received deliveries
+    pub deliveries: VecDeque<(Duration, Entity)>,
+    /// Structures whose porters got lost on the way to a reachable destination
+    pub losses: VecDeque<(Duration, Entity)>,
+    /// Structures that dispatched porters without any reachable destination
+    pub unreachable: VecDeque<(Duration, Entity)>,
+}
+
+fn toggle_overlay(mut overlay: ResMut<LogisticsOverlay>) {
+    overlay.enabled = !overlay.enabled;
+}
+
+fn overlay_enabled(overlay: Res<LogisticsOverlay>) -> bool {
+    overlay.enabled
+}
+
+fn record_arrivals(
+    mut porter_arrivals: MessageReader<PorterArrival>,
+    slots: Query<&InInventory>,
+    mut heat: ResMut<LogisticsHeat>,
+    time: Res<Time>,
+) {
+    for PorterArrival { slot, .. } in porter_arrivals.read() {
+        if let Ok(InInventory(structure)) = slots.get(*slot) {
+            heat.deliveries.push_back((time.elapsed(), *structure));
+        }
+    }
+}
+
+fn record_losses(
+    mut porter_losses: MessageReader<PorterLost>,
+    porters: Query<&Porting>,
+    coords: Query<&Coord>,
+    walkables: Query<(), With<Walkable>>,
+    constructions: Res<Constructions>,
+    inventory: Query<&Inventory>,
+    drop_offs: Query<&DropOff>,
+    item_defs: Res<Assets<ItemDef>>,
+    mut heat: ResMut<LogisticsHeat>,
+    time: Res<Time>,
+) {
+    for PorterLost(porter) in porter_losses.read() {
+        let Ok(porting) = porters.get(*porter) else {
+            continue;
+        };
+
+        let Ok(origin) = coords.get(porting.origin) else {
+            continue;
+        };
+
+        let accepts_item = |construction: &Entity| {
+            inventory.iter_descendants(*construction).any(|slot| {
+                drop_offs
+                    .get(slot)
+                    .is_ok_and(|drop_off| drop_off.accepts(&porting.item, &item_defs))
+            })
+        };
+
+        // Spreading through the origin's own tiles covers every side of larger footprints
+        let is_walkable = |coord: &IVec2| {
+            constructions.get(coord).is_some_and(|construction| {
+                walkables.contains(*construction) || *construction == porting.origin
+            })
+        };
+
+        let reachable = reachable_tiles(origin.0, is_walkable)
+            .iter()
+            .flat_map(|coord| CARDINALS.iter().map(move |c| coord + c))
+            .filter_map(|coord| constructions.get(&coord))
+            .any(accepts_item);
+
+        if reachable {
+            heat.losses.push_back((time.elapsed(), porting.origin));
+        } else {
+            heat.unreachable.push_back((time.elapsed(), porting.origin));
+        }
+    }
+}
+
+/// Walkable tiles connected to the tile at `origin`, not including the origin itself
+fn reachable_tiles(origin: IVec2, is_walkable: impl Fn(&IVec2) -> bool) -> HashSet<IVec2> {
+    let mut reached = HashSet::new();
+    let mut frontier = VecDeque::from([origin]);
+
+    while let Some(coord) = frontier.pop_front() {
+        for neighbor in CARDINALS.iter().map(|c| coord + c) {
+            if neighbor != origin && is_walkable(&neighbor) && reached.insert(neighbor) {
+                frontier.push_back(neighbor);
+            }
+        }
+    }
+
+    reached
+}
+
+fn expire_heat(mut heat: ResMut<LogisticsHeat>, time: Res<Time>) {
+    let Some(cutoff) = time.elapsed().checked_sub(HEAT_WINDOW) else {
+        return;
+    };
+
+    let heat = &mut *heat;
+
+    heat.deliveries.retain(|(at, _)| *at >= cutoff);
+    heat.losses.retain(|(at, _)| *at >= cutoff);
+    heat.unreachable.retain(|(at, _)| *at >= cutoff);
+}
+
+/// Tints path segments from blue to red by their porter throughput, and marks structures that
+/// received deliveries, lost porters or dispatched porters with nowhere to go
+fn tint_overlay(
+    heat: Res<LogisticsHeat>,
+    traffic: Res<TileTraffic>,
+    mut paths: Query<(&Coord, &mut Sprite, &PickingInteraction), With<PathSegment>>,
+    mut structures: Query<(Entity, &mut Sprite, &PickingInteraction), With<Structure>>,
+) {
+    let busiest = traffic
+        .throughput
+        .values()
+        .copied()
+        .fold(0.0, f32::max)
+        .max(f32::EPSILON);
+
+    for (coord, mut sprite, interaction) in paths.iter_mut() {
+        if *interaction != PickingInteraction::None {
+            continue;
+        }
+
+        if let Some(throughput) = traffic.throughput.get(&coord.0) {
+            let heat = throughput / busiest;
+            sprite.color = Color::hsl(240.0 * (1.0 - heat), 1.0, 0.5);
+        }
+    }
+
+    let marked = |events: &VecDeque<(Duration, Entity)>| -> HashSet<Entity> {
+        events.iter().map(|(_, entity)| *entity).collect()
+    };
+
+    let delivered = marked(&heat.deliveries);
+    let lost = marked(&heat.losses);
+    let unreachable = marked(&heat.unreachable);
+
+    for (structure, mut sprite, interaction) in structures.iter_mut() {
+        if *interaction != PickingInteraction::None {
+            continue;
+        }
+
+        if unreachable.contains(&structure) {
+            sprite.color = UNREACHABLE_COLOR;
+        } else if lost.contains(&structure) {
+            sprite.color = LOST_PORTER_COLOR;
+        } else if delivered.contains(&structure) {
+            sprite.color = DELIVERY_COLOR;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reachable_tiles_follow_connected_walkables() {
+        let walkable = HashSet::from([
+            IVec2::new(1, 0),
+            IVec2::new(2, 0),
+            IVec2::new(2, 1),
+            IVec2::new(5, 5),
+        ]);
+
+        let reached = reachable_tiles(IVec2::ZERO, |coord| walkable.contains(coord));
+
+        assert_eq!(
+            reached,
+            HashSet::from([IVec2::new(1, 0), IVec2::new(2, 0), IVec2::new(2, 1)])
+        );
+    }
+
+    #[test]
+    fn test_reachable_tiles_without_walkable_neighbours() {
+        let reached = reachable_tiles(IVec2::ZERO, |_| false);
+
+        assert!(reached.is_empty());
+    }
+}
@@ -1,9 +1,27 @@
 use bevy::prelude::*;
 
+pub mod biome;
 pub mod construction;
+pub mod construction_site;
 pub mod demolition;
+pub mod drag_placement;
+pub mod fog;
+pub mod generation;
+pub mod history;
+pub mod logistics_overlay;
 pub mod tilemap;
 
 pub fn plugin(app: &mut App) {
-    app.add_plugins((construction::plugin, demolition::plugin, tilemap::plugin));
+    app.add_plugins((
+        biome::plugin,
+        construction::plugin,
+        construction_site::plugin,
+        demolition::plugin,
+        drag_placement::plugin,
+        fog::plugin,
+        generation::plugin,
+        history::plugin,
+        logistics_overlay::plugin,
+        tilemap::plugin,
+    ));
 }
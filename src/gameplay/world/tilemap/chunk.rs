@@ -6,7 +6,12 @@ use bevy_ecs_tilemap::prelude::*;
 use crate::{
     gameplay::{
         sprite_sort::ZIndexSprite,
-        world::tilemap::{CHUNK_SIZE, TILE_OFFSET, TILE_SIZE},
+        world::{
+            biome::Biomes,
+            fog::{FogOfWar, tile_color},
+            generation::{ChunkPlan, WorldGen},
+            tilemap::{TILE_OFFSET, TILE_SIZE},
+        },
     },
     screens::Screen,
 };
@@ -43,25 +48,25 @@ pub struct World;
 #[reflect(Component)]
 pub struct Chunk(pub IVec2);
 
-const CHUNK_RENDER_DISTANCE: UVec2 = UVec2::new(3, 3);
+/// Maps chunk positions to translations for chunks of the given size
+pub fn chunk_matrix(chunk_size: UVec2) -> Mat2 {
+    let chunk_size_pixels = chunk_size.as_vec2() * TILE_OFFSET;
 
-const CHUNK_SIZE_PIXELS: Vec2 = Vec2::new(
-    CHUNK_SIZE.x as f32 * TILE_OFFSET.x,
-    CHUNK_SIZE.y as f32 * TILE_OFFSET.y,
-);
-
-pub const CHUNK_MATRIX: Mat2 = Mat2::from_cols(
-    Vec2::new(CHUNK_SIZE_PIXELS.x * 0.5, -(CHUNK_SIZE_PIXELS.y * 0.5)),
-    Vec2::new(CHUNK_SIZE_PIXELS.x * 0.5, CHUNK_SIZE_PIXELS.y * 0.5),
-);
+    Mat2::from_cols(
+        Vec2::new(chunk_size_pixels.x * 0.5, -(chunk_size_pixels.y * 0.5)),
+        Vec2::new(chunk_size_pixels.x * 0.5, chunk_size_pixels.y * 0.5),
+    )
+}
 
-pub fn translation_to_chunk(translation: &Vec2) -> Chunk {
-    let ivec = (CHUNK_MATRIX.inverse() * translation).round().as_ivec2();
+pub fn translation_to_chunk(translation: &Vec2, chunk_size: UVec2) -> Chunk {
+    let ivec = (chunk_matrix(chunk_size).inverse() * translation)
+        .round()
+        .as_ivec2();
     Chunk(ivec)
 }
 
-pub fn chunk_to_translation(chunk: &Chunk) -> Vec2 {
-    CHUNK_MATRIX * chunk.0.as_vec2()
+pub fn chunk_to_translation(chunk: &Chunk, chunk_size: UVec2) -> Vec2 {
+    chunk_matrix(chunk_size) * chunk.0.as_vec2()
 }
 
 fn spawn_world(mut commands: Commands) {
@@ -76,62 +81,40 @@ fn spawn_world(mut commands: Commands) {
 fn spawn_chunks_around_camera(
     mut commands: Commands,
     camera_transform: Single<&Transform, With<Camera>>,
-    asset_server: Res<AssetServer>,
+    biomes: Biomes,
+    fog: Res<FogOfWar>,
+    world_gen: Res<WorldGen>,
     mut chunk_manager: ResMut<ChunkManager>,
     world: Single<Entity, With<World>>,
 ) {
-    let focused_chunk = translation_to_chunk(&camera_transform.translation.xy());
+    let chunk_size = world_gen.config.chunk_size;
+    let render_distance = world_gen.config.render_distance.as_ivec2();
+    let focused_chunk = translation_to_chunk(&camera_transform.translation.xy(), chunk_size);
 
-    for y in (focused_chunk.y - CHUNK_RENDER_DISTANCE.y as i32)
-        ..=(focused_chunk.y + CHUNK_RENDER_DISTANCE.y as i32)
-    {
-        for x in (focused_chunk.x - CHUNK_RENDER_DISTANCE.x as i32)
-            ..=(focused_chunk.x + CHUNK_RENDER_DISTANCE.x as i32)
-        {
+    for y in (focused_chunk.y - render_distance.y)..=(focused_chunk.y + render_distance.y) {
+        for x in (focused_chunk.x - render_distance.x)..=(focused_chunk.x + render_distance.x) {
             let chunk_pos = IVec2::new(x, y);
 
             if chunk_manager.spawned_chunks.contains_key(&chunk_pos) {
                 continue;
             }
 
-            let chunk = commands.spawn_empty().id();
-            let map_size = TilemapSize::from(CHUNK_SIZE);
-            let tile_size = TilemapTileSize::from(TILE_SIZE);
-            let grid_size = TilemapGridSize::from(TILE_OFFSET);
-            let mut storage = TileStorage::empty(map_size);
-
-            fill_tilemap_rect(
-                TileTextureIndex(0),
-                TilePos::new(0, 0),
-                TilemapSize::from(CHUNK_SIZE),
-                TilemapId(chunk),
-                &mut commands,
-                &mut storage,
-            );
-
-            let chunk_translation = chunk_to_translation(&Chunk(chunk_pos));
-            let transform = Transform::from_translation(chunk_translation.extend(0.0));
-
-            commands.entity(chunk).insert((
-                Name::new("Chunk"),
-                Chunk(chunk_pos),
-                ChildOf(*world),
-                ZIndexSprite(0),
-                TilemapBundle {
-                    transform,
-                    grid_size,
-                    size: map_size,
-                    storage,
-                    texture: TilemapTexture::Single(asset_server.load("tiles/grass.png")),
-                    tile_size,
-                    map_type: TilemapType::Isometric(IsoCoordSystem::Diamond),
-                    render_settings: TilemapRenderSettings {
-                        render_chunk_size: UVec2::new(map_size.x, 1),
-                        y_sort: true,
-                    },
-                    ..default()
-                },
-            ));
+            let chunk_translation = chunk_to_translation(&Chunk(chunk_pos), chunk_size);
+            let plan = world_gen.pipeline.generate(chunk_pos);
+
+            let chunk = commands
+                .spawn((
+                    Name::new("Chunk"),
+                    Chunk(chunk_pos),
+                    ChildOf(*world),
+                    ZIndexSprite(0),
+                    Transform::from_translation(chunk_translation.extend(0.0)),
+                    Visibility::default(),
+                ))
+                .id();
+
+            spawn_chunk_layers(&mut commands, chunk, &plan, &biomes, &fog);
+            commands.entity(chunk).insert(plan);
 
             chunk_manager.spawned_chunks.insert(chunk_pos, chunk);
 
@@ -140,17 +123,84 @@ fn spawn_chunks_around_camera(
     }
 }
 
+/// Spawns a tilemap under the chunk for each tileset its biomes use
+fn spawn_chunk_layers(
+    commands: &mut Commands,
+    chunk: Entity,
+    plan: &ChunkPlan,
+    biomes: &Biomes,
+    fog: &FogOfWar,
+) {
+    let map_size = TilemapSize::from(plan.size);
+    let mut layers: HashMap<Handle<Image>, (Entity, TileStorage)> = HashMap::new();
+
+    for (local, coord, tile) in plan.iter() {
+        let Some(biome) = tile.biome.and_then(|biome| biomes.by_index(biome)) else {
+            continue;
+        };
+
+        let (layer, storage) = layers.entry(biome.tileset.clone()).or_insert_with(|| {
+            (
+                commands
+                    .spawn((Name::new("Chunk Layer"), ChildOf(chunk)))
+                    .id(),
+                TileStorage::empty(map_size),
+            )
+        });
+
+        let position = TilePos::new(local.x, local.y);
+        let tile = commands
+            .spawn((
+                TileBundle {
+                    position,
+                    texture_index: TileTextureIndex(biome.texture_index(coord)),
+                    tilemap_id: TilemapId(*layer),
+                    color: TileColor(tile_color(
+                        biome.ground_tint(tile.terrain),
+                        fog.is_revealed(coord),
+                    )),
+                    ..default()
+                },
+                ChildOf(*layer),
+            ))
+            .id();
+
+        storage.set(&position, tile);
+    }
+
+    for (tileset, (layer, storage)) in layers {
+        commands.entity(layer).insert(TilemapBundle {
+            grid_size: TilemapGridSize::from(TILE_OFFSET),
+            size: map_size,
+            storage,
+            texture: TilemapTexture::Single(tileset),
+            tile_size: TilemapTileSize::from(TILE_SIZE),
+            map_type: TilemapType::Isometric(IsoCoordSystem::Diamond),
+            render_settings: TilemapRenderSettings {
+                render_chunk_size: UVec2::new(map_size.x, 1),
+                y_sort: true,
+            },
+            ..default()
+        });
+    }
+}
+
 fn despawn_chunks(
     mut commands: Commands,
     camera_transform: Single<&Transform, With<Camera>>,
     mut chunk_manager: ResMut<ChunkManager>,
     chunk_query: Query<(Entity, &Chunk)>,
+    world_gen: Res<WorldGen>,
 ) {
-    let focused_chunk = translation_to_chunk(&camera_transform.translation.xy());
+    let render_distance = world_gen.config.render_distance;
+    let focused_chunk = translation_to_chunk(
+        &camera_transform.translation.xy(),
+        world_gen.config.chunk_size,
+    );
 
     for (chunk, chunk_coord) in chunk_query {
-        if chunk_coord.x.abs_diff(focused_chunk.x) > CHUNK_RENDER_DISTANCE.x
-            || chunk_coord.y.abs_diff(focused_chunk.y) > CHUNK_RENDER_DISTANCE.y
+        if chunk_coord.x.abs_diff(focused_chunk.x) > render_distance.x
+            || chunk_coord.y.abs_diff(focused_chunk.y) > render_distance.y
         {
             commands.trigger(ChunkUnloaded { chunk });
             commands.entity(chunk).despawn();
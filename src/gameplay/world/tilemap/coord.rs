@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::gameplay::world::tilemap::TILE_OFFSET;
+use crate::gameplay::{structure::footprint::Footprint, world::tilemap::TILE_OFFSET};
 
 pub fn plugin(app: &mut App) {
     app.add_systems(PostUpdate, translate_coord_to_transform);
@@ -25,8 +25,27 @@ pub fn coord_to_translation(coord: &Coord) -> Vec2 {
     TILE_MATRIX * coord.0.as_vec2()
 }
 
-fn translate_coord_to_transform(coord_query: Query<(&mut Transform, &Coord), Changed<Coord>>) {
-    for (mut transform, coord) in coord_query {
-        transform.translation = coord_to_translation(coord).extend(transform.translation.z);
+/// Pseudo-random value for the tile, stable across runs, with `salt` telling apart its uses
+pub fn tile_hash(coord: IVec2, salt: u32) -> u32 {
+    let mut hash = (coord.x as u32).wrapping_mul(0x9E37_79B1)
+        ^ (coord.y as u32).wrapping_mul(0x85EB_CA77)
+        ^ salt.wrapping_mul(0xC2B2_AE3D);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2C1B_3C6D);
+    hash ^ (hash >> 12)
+}
+
+/// Places entities on their tile, or in the middle of the tiles they cover
+fn translate_coord_to_transform(
+    coord_query: Query<
+        (&mut Transform, &Coord, Option<&Footprint>),
+        Or<(Changed<Coord>, Changed<Footprint>)>,
+    >,
+) {
+    for (mut transform, coord, footprint) in coord_query {
+        let centre = footprint.map_or(Vec2::ZERO, Footprint::centre);
+
+        transform.translation =
+            (coord_to_translation(coord) + centre).extend(transform.translation.z);
     }
 }
@@ -3,6 +3,7 @@ use bevy::prelude::*;
 use crate::{
     gameplay::world::{
         construction::ValidPlacement,
+        drag_placement::DragPlacement,
         tilemap::coord::{Coord, translation_to_coord},
     },
     input::cursor::CursorPosition,
@@ -11,7 +12,6 @@ use crate::{
 pub mod chunk;
 pub mod coord;
 
-pub const CHUNK_SIZE: UVec2 = UVec2 { x: 16, y: 16 };
 pub const TILE_SIZE: Vec2 = Vec2 { x: 512.0, y: 256.0 };
 pub const TILE_OFFSET: Vec2 = Vec2 { x: 256.0, y: 128.0 };
 
@@ -34,11 +34,18 @@ pub fn plugin(app: &mut App) {
 pub struct TileClicked(pub Coord);
 
 fn on_click(
-    _click: On<Pointer<Click>>,
+    click: On<Pointer<Click>>,
+    ui_nodes: Query<(), With<Node>>,
     cursor_position: Res<CursorPosition>,
     mut tile_clicks: MessageWriter<TileClicked>,
     valid_placement: Res<ValidPlacement>,
+    drag_placement: Res<DragPlacement>,
 ) {
+    // Runs are placed when the drag ends, and clicks on the HUD don't reach the tiles beneath
+    if drag_placement.is_dragging() || ui_nodes.contains(click.entity) {
+        return;
+    }
+
     if valid_placement.0 {
         let coord = translation_to_coord(&cursor_position.0);
         tile_clicks.write(TileClicked(coord));
@@ -17,10 +17,22 @@ pub const DEFAULT_KEY_HOTBAR_6: KeyCode = KeyCode::Digit6;
 pub const DEFAULT_KEY_HOTBAR_7: KeyCode = KeyCode::Digit7;
 pub const DEFAULT_KEY_HOTBAR_8: KeyCode = KeyCode::Digit8;
 pub const DEFAULT_KEY_HOTBAR_9: KeyCode = KeyCode::Digit9;
+pub const DEFAULT_KEY_HOTBAR_10: KeyCode = KeyCode::Digit0;
+pub const DEFAULT_KEY_KEEP_SELECTION: KeyCode = KeyCode::ControlLeft;
 
 pub const DEFAULT_KEY_DEMOLISH: KeyCode = KeyCode::KeyF;
 pub const DEFAULT_KEY_MULTI_SELECT: KeyCode = KeyCode::ShiftLeft;
 
+pub const DEFAULT_KEY_ROTATE: KeyCode = KeyCode::KeyR;
+pub const DEFAULT_KEY_COPY_BLUEPRINT: KeyCode = KeyCode::KeyB;
+pub const DEFAULT_KEY_PASTE_BLUEPRINT: KeyCode = KeyCode::KeyV;
+
+pub const DEFAULT_KEY_UNDO: KeyCode = KeyCode::KeyZ;
+pub const DEFAULT_KEY_REDO: KeyCode = KeyCode::KeyY;
+
+pub const DEFAULT_KEY_LOGISTICS_OVERLAY: KeyCode = KeyCode::KeyL;
+pub const DEFAULT_KEY_TRAFFIC_SIMULATION: KeyCode = KeyCode::KeyK;
+
 pub const DEFAULT_KEY_DEBUG_MODE: KeyCode = KeyCode::Backquote;
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq, Reflect, Debug)]
@@ -38,18 +50,34 @@ pub enum Action {
     Hotbar7,
     Hotbar8,
     Hotbar9,
+    Hotbar10,
+    KeepSelection,
 
     Demolish,
     MultiSelect,
 
+    Rotate,
+    CopyBlueprint,
+    PasteBlueprint,
+
+    Undo,
+    Redo,
+
+    LogisticsOverlay,
+    TrafficSimulation,
+
     DebugMode,
 }
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<InputMap>();
     app.init_resource::<InputActions>();
+    app.init_resource::<TextEntryFocus>();
 
-    app.add_systems(PreUpdate, populate_keyboard_actions);
+    app.add_systems(
+        PreUpdate,
+        populate_keyboard_actions.run_if(not(text_entry_focused)),
+    );
     app.add_systems(PostUpdate, clear_actions);
 }
 
@@ -66,6 +94,15 @@ pub struct InputActions {
     pub pressed: HashSet<Action>,
 }
 
+/// Whether a text field is taking keyboard input, suppressing all actions meanwhile
+#[derive(Resource, Reflect, Debug, Default, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct TextEntryFocus(pub bool);
+
+fn text_entry_focused(focus: Res<TextEntryFocus>) -> bool {
+    focus.0
+}
+
 impl Default for InputMap {
     fn default() -> Self {
         Self {
@@ -84,9 +121,21 @@ impl Default for InputMap {
                 (Action::Hotbar7, DEFAULT_KEY_HOTBAR_7),
                 (Action::Hotbar8, DEFAULT_KEY_HOTBAR_8),
                 (Action::Hotbar9, DEFAULT_KEY_HOTBAR_9),
+                (Action::Hotbar10, DEFAULT_KEY_HOTBAR_10),
+                (Action::KeepSelection, DEFAULT_KEY_KEEP_SELECTION),
                 // Demolish
                 (Action::Demolish, DEFAULT_KEY_DEMOLISH),
                 (Action::MultiSelect, DEFAULT_KEY_MULTI_SELECT),
+                // Placement
+                (Action::Rotate, DEFAULT_KEY_ROTATE),
+                (Action::CopyBlueprint, DEFAULT_KEY_COPY_BLUEPRINT),
+                (Action::PasteBlueprint, DEFAULT_KEY_PASTE_BLUEPRINT),
+                // History
+                (Action::Undo, DEFAULT_KEY_UNDO),
+                (Action::Redo, DEFAULT_KEY_REDO),
+                // Overlays
+                (Action::LogisticsOverlay, DEFAULT_KEY_LOGISTICS_OVERLAY),
+                (Action::TrafficSimulation, DEFAULT_KEY_TRAFFIC_SIMULATION),
                 // Debug
                 (Action::DebugMode, DEFAULT_KEY_DEBUG_MODE),
             ]),
@@ -13,6 +13,8 @@ mod input;
 mod screens;
 mod widgets;
 
+pub use gameplay::world::generation::preview;
+
 pub struct FactoryGamePlugin;
 
 impl Plugin for FactoryGamePlugin {
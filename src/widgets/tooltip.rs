@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use bevy::prelude::*;
 
 pub const TOOLTIP_POINTER_OFFSET: Vec2 = Vec2 { x: 0.0, y: -32.0 };
@@ -9,20 +11,35 @@ pub(super) fn plugin(app: &mut App) {
         .add_observer(follow_mouse);
 }
 
-#[derive(Component, Reflect, Debug)]
+/// Text wanted in the tooltip by each source, the highest of them being shown
+#[derive(Component, Reflect, Debug, Default)]
 #[reflect(Component)]
-pub struct Tooltip;
+pub struct Tooltip(BTreeMap<TooltipSource, String>);
+
+/// Feature owning a tooltip, so sources only ever replace or hide their own. While several want
+/// one at the same time the one declared last is shown.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TooltipSource {
+    Porter,
+    Town,
+    Placement,
+    DragPlacement,
+    Blueprint,
+}
 
 #[derive(Event, Reflect, Debug)]
-pub struct ShowTooltip(pub String);
+pub struct ShowTooltip {
+    pub source: TooltipSource,
+    pub text: String,
+}
 
 #[derive(Event, Reflect, Debug)]
-pub struct HideTooltip;
+pub struct HideTooltip(pub TooltipSource);
 
 fn spawn_tooltip(mut commands: Commands) {
     commands.spawn((
         Name::new("Tooltip"),
-        Tooltip,
+        Tooltip::default(),
         Node::default(),
         Text::default(),
         BackgroundColor(Color::hsl(210.0, 0.13, 0.5)),
@@ -33,20 +50,43 @@ fn spawn_tooltip(mut commands: Commands) {
 
 fn on_show_tooltip(
     show_tooltip: On<ShowTooltip>,
-    tooltip_query: Single<(&mut Text, &mut Visibility), With<Tooltip>>,
+    tooltip_query: Single<(&mut Tooltip, &mut Text, &mut Visibility)>,
 ) {
-    let (mut text, mut visibility) = tooltip_query.into_inner();
-    text.0 = show_tooltip.0.to_owned();
-    *visibility = Visibility::Inherited;
+    let (mut tooltip, text, visibility) = tooltip_query.into_inner();
+
+    if tooltip.0.get(&show_tooltip.source) == Some(&show_tooltip.text) {
+        return;
+    }
+
+    tooltip
+        .0
+        .insert(show_tooltip.source, show_tooltip.text.clone());
+    refresh_tooltip(&tooltip, text, visibility);
 }
 
 fn on_hide_tooltip(
-    _hide_tooltip: On<HideTooltip>,
-    tooltip_query: Single<(&mut Text, &mut Visibility), With<Tooltip>>,
+    hide_tooltip: On<HideTooltip>,
+    tooltip_query: Single<(&mut Tooltip, &mut Text, &mut Visibility)>,
 ) {
-    let (mut text, mut visibility) = tooltip_query.into_inner();
-    text.0 = String::default();
-    *visibility = Visibility::Hidden;
+    let (mut tooltip, text, visibility) = tooltip_query.into_inner();
+
+    if tooltip.0.remove(&hide_tooltip.0).is_some() {
+        refresh_tooltip(&tooltip, text, visibility);
+    }
+}
+
+/// Shows the text of the highest source still wanting the tooltip, hiding it if there is none
+fn refresh_tooltip(tooltip: &Tooltip, mut text: Mut<Text>, mut visibility: Mut<Visibility>) {
+    match tooltip.0.last_key_value() {
+        Some((_, shown)) => {
+            text.0 = shown.clone();
+            *visibility = Visibility::Inherited;
+        }
+        None => {
+            text.0 = String::default();
+            *visibility = Visibility::Hidden;
+        }
+    }
 }
 
 fn follow_mouse(